@@ -9,15 +9,28 @@ use structopt::StructOpt;
 async fn main() -> Result<()> {
     let opt = Opt::from_args();
 
-    if let Some(cmd) = opt.cmd {
+    if let Some(cmd) = &opt.cmd {
         match cmd {
-            OptSubcommands::Get { property: n } => match out::get_property(&n).await {
+            OptSubcommands::Get { property: n } => match out::get_property(n).await {
                 Ok(_) => {}
                 Err(e) => eprintln!("Failed to get property {} - {}", n, e),
             },
+            OptSubcommands::Ps(ps) => match ps_out(ps, &opt) {
+                Ok(out) => println!("{}", out),
+                Err(e) => eprintln!("Failed to list processes - {}", e),
+            },
+            OptSubcommands::Kill(kill) => match kill_out(kill) {
+                Ok(out) => println!("{}", out),
+                Err(e) => eprintln!("Failed to signal processes - {}", e),
+            },
+            OptSubcommands::Watch(watch) => {
+                if let Err(e) = watch_out(watch, &opt).await {
+                    eprintln!("Failed to watch processes - {}", e)
+                }
+            }
         }
     } else {
-        let p = PcInfo::new().await;
+        let p = PcInfo::from_opts(&opt).await;
         let mut s = String::new();
         if opt.json || opt.prettyjson {
             match json_out(&p, &opt) {
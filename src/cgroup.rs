@@ -0,0 +1,159 @@
+use super::*;
+
+/// Single line of `/proc/[pid]/cgroup`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CgroupEntry {
+    /// Hierarchy id, always 0 for the unified (v2) hierarchy
+    pub hierarchy: u32,
+    /// Controllers bound to a v1 hierarchy like `cpu,cpuacct`, empty for v2
+    pub controllers: Vec<String>,
+    /// Path relative to the hierarchy mount like `/system.slice/sshd.service`
+    pub path: String,
+}
+impl CgroupEntry {
+    pub fn is_unified(&self) -> bool {
+        self.hierarchy == 0 && self.controllers.is_empty()
+    }
+}
+
+/// Cgroups a process belongs to, one per mounted hierarchy
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Cgroups(pub Vec<CgroupEntry>);
+impl Cgroups {
+    /// Returns path of v1 hierarchy with `controller`
+    pub fn v1_path(&self, controller: &str) -> Option<&str> {
+        self.0.iter().find(|e| e.controllers.iter().any(|c| c == controller)).map(|e| e.path.as_str())
+    }
+
+    /// Returns path in the unified hierarchy
+    pub fn v2_path(&self) -> Option<&str> {
+        self.0.iter().find(|e| e.is_unified()).map(|e| e.path.as_str())
+    }
+
+    /// Returns the most specific path of the process, preferring v2 unless it's the root
+    /// of a hybrid setup where v1 controllers do the accounting
+    pub fn path(&self) -> &str {
+        match (self.v2_path(), self.v1_path("memory").or_else(|| self.v1_path("name=systemd"))) {
+            (Some("/"), Some(v1)) => v1,
+            (Some(v2), _) => v2,
+            (None, Some(v1)) => v1,
+            (None, None) => "/",
+        }
+    }
+
+    /// Returns cgroups one level up in every hierarchy, or `None` if all of them are roots
+    pub fn parent(&self) -> Option<Cgroups> {
+        if self.0.iter().all(|e| e.path == "/") {
+            return None;
+        }
+        let parent = |path: &str| match path.rsplit_once('/') {
+            Some(("", _)) | None => "/".to_string(),
+            Some((parent, _)) => parent.to_string(),
+        };
+        Some(Cgroups(self.0.iter().map(|e| CgroupEntry { path: parent(&e.path), ..e.clone() }).collect()))
+    }
+
+    /// Returns paths relative to the roots of mounted hierarchies, so they can be joined with
+    /// mount points. Paths outside of the mounted roots are left as they are.
+    pub fn relative_to(&self, mounts: &CgroupMounts) -> Cgroups {
+        let relative = |entry: &CgroupEntry| {
+            let root = mounts.root(entry).filter(|root| *root != "/");
+            let path = match root {
+                Some(root) if entry.path == root => "/",
+                Some(root) => entry.path.strip_prefix(root).filter(|rest| rest.starts_with('/')).unwrap_or(&entry.path),
+                None => &entry.path,
+            };
+            CgroupEntry { path: path.to_string(), ..entry.clone() }
+        };
+        Cgroups(self.0.iter().map(relative).collect())
+    }
+
+    /// Returns systemd unit like `sshd.service` or `session-2.scope` the process runs in
+    pub fn unit(&self) -> Option<&str> {
+        self.path().rsplit('/').find(|c| c.ends_with(".service") || c.ends_with(".scope"))
+    }
+}
+impl IntoIterator for Cgroups {
+    type Item = CgroupEntry;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Cgroup hierarchy mounted in the current mount namespace
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CgroupMount {
+    /// Controllers of a v1 hierarchy like `cpu`, `cpuacct` or `name=systemd`, empty for v2
+    pub controllers: Vec<String>,
+    /// Cgroup mounted as the hierarchy root. Containers without a cgroup namespace
+    /// see their own cgroup like `/docker/<id>` here and in `/proc/self/cgroup`.
+    pub root: String,
+}
+
+/// Cgroup hierarchies from `/proc/self/mountinfo`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CgroupMounts(pub Vec<CgroupMount>);
+impl CgroupMounts {
+    /// Returns root of the hierarchy `entry` belongs to
+    pub fn root(&self, entry: &CgroupEntry) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|m| match entry.is_unified() {
+                true => m.controllers.is_empty(),
+                false => entry.controllers.iter().any(|c| m.controllers.contains(c)),
+            })
+            .map(|m| m.root.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CgroupIo {
+    /// Block device as `major:minor`
+    pub device: String,
+    pub rbytes: u64,
+    pub wbytes: u64,
+    pub rios: u64,
+    pub wios: u64,
+}
+
+/// Resource usage and limits of a cgroup. Limits are `None` when unlimited,
+/// everything else is `None` when the controller isn't enabled for the group.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CgroupStats {
+    pub path: String,
+    pub memory_current: Option<u64>,
+    pub memory_max: Option<u64>,
+    /// Total cpu time used by the group in microseconds
+    pub cpu_usage_usec: Option<u64>,
+    pub cpu_user_usec: Option<u64>,
+    pub cpu_system_usec: Option<u64>,
+    /// Cpu bandwidth as quota and period in microseconds
+    pub cpu_max: Option<(u64, u64)>,
+    /// Cpus the group is allowed to run on, empty if unknown
+    pub cpuset: Vec<u32>,
+    pub pids_current: Option<u64>,
+    pub pids_max: Option<u64>,
+    pub io: Vec<CgroupIo>,
+}
+impl CgroupStats {
+    /// Returns how many cpus worth of time the group may use
+    pub fn cpu_limit(&self) -> Option<f64> {
+        self.cpu_max.filter(|(_, period)| *period > 0).map(|(quota, period)| quota as f64 / period as f64)
+    }
+}
+
+/// Effective limits of the cgroup rustop runs in. Each is `None` unless it is lower
+/// than the host's resources, which is what `/proc/meminfo` and `/proc/cpuinfo` show.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ResourceLimits {
+    pub memory: Option<u64>,
+    /// Number of cpus worth of time from cpu quota or cpuset, whichever is lower
+    pub cpus: Option<f64>,
+}
+impl ResourceLimits {
+    pub fn is_limited(&self) -> bool {
+        self.memory.is_some() || self.cpus.is_some()
+    }
+}
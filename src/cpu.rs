@@ -0,0 +1,158 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct LoadAvg {
+    /// Load average over the last minute
+    pub one: f32,
+    /// Load average over the last 5 minutes
+    pub five: f32,
+    /// Load average over the last 15 minutes
+    pub fifteen: f32,
+    /// Number of currently runnable scheduling entities
+    pub running_tasks: u32,
+    /// Number of scheduling entities that currently exist on the system
+    pub total_tasks: u32,
+    /// PID of the process that was most recently created
+    pub last_pid: u32,
+}
+impl LoadAvg {
+    /// Returns (1, 5, 15) minute load averages divided by the number of cores
+    pub fn per_core(&self, cores: usize) -> (f32, f32, f32) {
+        match cores {
+            0 => (self.one, self.five, self.fifteen),
+            n => (self.one / n as f32, self.five / n as f32, self.fifteen / n as f32),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Processor {
+    /// Logical processor number as seen by the kernel
+    pub id: u32,
+    /// `vendor_id` on x86, `CPU implementer` on ARM
+    pub vendor: String,
+    pub model_name: String,
+    pub family: Option<u32>,
+    /// `model` on x86, `CPU part` on ARM
+    pub model: Option<u32>,
+    /// `stepping` on x86, `CPU revision` on ARM
+    pub stepping: Option<u32>,
+    pub microcode: Option<String>,
+    /// Cache size reported in /proc/cpuinfo in bytes
+    pub cache_size: Option<u64>,
+    /// Socket this processor belongs to
+    pub physical_id: u32,
+    /// Core id within the socket
+    pub core_id: u32,
+    pub mhz: Option<f32>,
+    /// `flags` on x86, `Features` on ARM
+    pub flags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct CpuCache {
+    pub level: u8,
+    /// Data, Instruction or Unified
+    pub kind: String,
+    /// Cache size in bytes
+    pub size: u64,
+}
+
+type Processors = Vec<Processor>;
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct CpuTopology {
+    pub sockets: usize,
+    pub cores: usize,
+    pub threads: usize,
+    /// Caches available to the first processor
+    pub caches: Vec<CpuCache>,
+    pub processors: Processors,
+}
+impl CpuTopology {
+    /// Recalculates socket, core and thread counts from processors
+    pub(crate) fn count(&mut self) {
+        let mut sockets = self.processors.iter().map(|p| p.physical_id).collect::<Vec<u32>>();
+        sockets.sort_unstable();
+        sockets.dedup();
+        let mut cores = self.processors.iter().map(|p| (p.physical_id, p.core_id)).collect::<Vec<(u32, u32)>>();
+        cores.sort_unstable();
+        cores.dedup();
+        self.sockets = sockets.len();
+        self.cores = cores.len();
+        self.threads = self.processors.len();
+    }
+
+    /// Returns true if every processor reports the given flag
+    pub fn has_flag(&self, flag: &str) -> bool {
+        !self.processors.is_empty() && self.processors.iter().all(|p| p.flags.iter().any(|f| f == flag))
+    }
+}
+
+impl IntoIterator for CpuTopology {
+    type Item = Processor;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.processors.into_iter()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct CoreFreq {
+    /// Logical processor number
+    pub cpu: u32,
+    /// Current frequency in kHz
+    pub cur: Option<u64>,
+    /// Minimum frequency allowed by the governor in kHz
+    pub min: Option<u64>,
+    /// Maximum frequency allowed by the governor in kHz
+    pub max: Option<u64>,
+    /// Minimum frequency supported by hardware in kHz
+    pub hw_min: Option<u64>,
+    /// Maximum frequency supported by hardware in kHz
+    pub hw_max: Option<u64>,
+    pub driver: Option<String>,
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+    pub energy_performance_preference: Option<String>,
+}
+
+type CoreFreqs = Vec<CoreFreq>;
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct CpuFreq {
+    /// Whether frequency boost (turbo) is enabled, `None` if unsupported
+    pub boost: Option<bool>,
+    pub cores: CoreFreqs,
+}
+impl CpuFreq {
+    /// Returns average current frequency of all cores in MHz
+    pub fn avg_mhz(&self) -> Option<f32> {
+        let freqs = self.cores.iter().filter_map(|c| c.cur).collect::<Vec<u64>>();
+        match freqs.len() {
+            0 => None,
+            n => Some(freqs.iter().sum::<u64>() as f32 / n as f32 / 1000.),
+        }
+    }
+
+    /// Returns distinct governors in use across all cores
+    pub fn governors(&self) -> Vec<&str> {
+        let mut governors = self.cores.iter().filter_map(|c| c.governor.as_deref()).collect::<Vec<&str>>();
+        governors.sort_unstable();
+        governors.dedup();
+        governors
+    }
+
+    /// Returns true if every core uses the given scaling governor
+    pub fn governor_is(&self, governor: &str) -> bool {
+        !self.cores.is_empty() && self.cores.iter().all(|c| c.governor.as_deref() == Some(governor))
+    }
+}
+
+impl IntoIterator for CpuFreq {
+    type Item = CoreFreq;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cores.into_iter()
+    }
+}
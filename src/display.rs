@@ -2,27 +2,103 @@ use super::*;
 use colored::*;
 use std::fmt::{Display, Formatter, Result};
 
+// Colours load normalised per core - green below 70%, yellow up to full saturation, red above
+fn colour_load(load: f32) -> ColoredString {
+    let s = format!("{:.2}", load);
+    if load < 0.7 {
+        s.green().bold()
+    } else if load < 1.0 {
+        s.yellow().bold()
+    } else {
+        s.red().bold()
+    }
+}
+
+// Colours a reading by how close it is to its critical limit
+fn colour_ratio(s: String, ratio: Option<f32>) -> ColoredString {
+    match ratio {
+        Some(r) if r >= 0.9 => s.red().bold(),
+        Some(r) if r >= 0.75 => s.yellow().bold(),
+        Some(_) => s.green().bold(),
+        None => s.normal(),
+    }
+}
+
+// Joins values that are present with a space
+fn join(values: &[&Option<String>]) -> String {
+    values.iter().filter_map(|v| v.as_deref()).collect::<Vec<&str>>().join(" ")
+}
+
+fn show_boost(boost: Option<bool>) -> ColoredString {
+    match boost {
+        Some(true) => "enabled".green().bold(),
+        Some(false) => "disabled".yellow().bold(),
+        None => "".normal(),
+    }
+}
+
+fn show_khz(khz: Option<u64>) -> String {
+    khz.map_or("-".to_string(), |khz| format!("{:.2}", khz as f32 / 1000.))
+}
+
 impl Display for PcInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (one, five, fifteen) = self.load.per_core(self.cpu_cores);
         write!(
             f,
             "┌──────────────────────────────────
 │ HOSTNAME:             {}
+│ OS:                   {}
 │ KERNEL VERSION:       {}
+│ ARCHITECTURE:         {}
+│ INIT:                 {}
+│ SYSTEM:               {}
+│ SERIAL:               {}
+│ BOARD:                {}
+│ BIOS:                 {}
+│ CHASSIS:              {}
+│ ENVIRONMENT:          {}
 │ UPTIME:               {}
+│ BOOT TIME:            {}
 │ CPU:                  {}
 │ CPU CLOCK:            {:.2} MHz
+│ GOVERNOR:             {}
+│ BOOST:                {}
+│ LOAD AVG:             {:.2} {:.2} {:.2}
+│ LOAD PER CORE:        {} {} {}
+│ TASKS:                {} running  {} total  last pid {}
 │ GRAPHICS CARD:        {}
 │ MEM:                  {}  {}
 │ MEMFREE:              {}  {}  {}%
 │ SWAP:                 {}  {}
 │ SWAPFREE:             {}  {}  {}%",
             self.hostname.bold().red(),
+            self.os.pretty_name.bold(),
             self.kernel_version.bold(),
+            self.os.architecture,
+            self.os.init,
+            join(&[&self.dmi.sys_vendor, &self.dmi.product_name, &self.dmi.product_version]).bold(),
+            self.dmi.product_serial.as_deref().unwrap_or(""),
+            join(&[&self.dmi.board_vendor, &self.dmi.board_name, &self.dmi.board_version]),
+            join(&[&self.dmi.bios_vendor, &self.dmi.bios_version, &self.dmi.bios_date]),
+            self.dmi.chassis_type.as_deref().unwrap_or(""),
+            self.environment.to_string().bold(),
             utils::conv_t(self.uptime).bold(),
+            self.os.boot_time.map(utils::conv_date).unwrap_or_default(),
             self.cpu.bold(),
             self.cpu_clock,
-            self.graphics_card.bold(),
+            self.cpu_freq.governors().join(" ").bold(),
+            show_boost(self.cpu_freq.boost),
+            self.load.one,
+            self.load.five,
+            self.load.fifteen,
+            colour_load(one),
+            colour_load(five),
+            colour_load(fifteen),
+            self.load.running_tasks.to_string().bold(),
+            self.load.total_tasks.to_string().bold(),
+            self.load.last_pid,
+            self.graphics_cards.join("\n│                       ").bold(),
             utils::conv_b(self.memory).bold(),
             self.memory.to_string().bold(),
             utils::conv_b(self.free_memory).bold(),
@@ -33,9 +109,261 @@ impl Display for PcInfo {
             utils::conv_b(self.free_swap).bold(),
             self.free_swap.to_string().bold(),
             utils::conv_p(self.swap, self.free_swap).to_string().bold(),
+        )?;
+        if let Some(kernel) = &self.kernel {
+            write!(
+                f,
+                "
+│ PROCS:                {} running  {} blocked
+│ CONTEXT SWITCHES:     {:.0}/s  interrupts {:.0}/s  forks {:.1}/s
+│ PAGE FAULTS:          {:.0}/s  major {:.0}/s
+│ SWAP IN/OUT:          {}/s  {}/s",
+                kernel.procs_running.to_string().bold(),
+                kernel.procs_blocked,
+                kernel.ctxsw,
+                kernel.interrupts,
+                kernel.forks,
+                kernel.page_faults,
+                kernel.major_faults,
+                utils::conv_b(kernel.swap_in as u64),
+                utils::conv_b(kernel.swap_out as u64),
+            )?;
+        }
+        if let Some(memory) = self.limits.memory {
+            write!(f, "\n│ MEM LIMIT:            {}  {}", utils::conv_b(memory).bold(), memory.to_string().bold())?;
+        }
+        if let Some(cpus) = self.limits.cpus {
+            write!(f, "\n│ CPU LIMIT:            {} of {} cpus", format!("{:.2}", cpus).bold(), self.cpu_cores)?;
+        }
+        if self.numa.is_numa() {
+            let nodes = self
+                .numa
+                .nodes
+                .iter()
+                .map(|n| format!("node{} {} free", n.id, utils::conv_b(n.mem_free)))
+                .collect::<Vec<String>>();
+            write!(f, "\n│ NUMA NODES:           {}", nodes.join(", ").bold())?;
+        }
+        for battery in &self.power.batteries {
+            write!(f, "\n│ BATTERY:              {}", battery)?;
+        }
+        for ac in &self.power.adapters {
+            write!(f, "\n│ AC ADAPTER:           {}", ac)?;
+        }
+        Ok(())
+    }
+}
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match (&self.container, &self.hypervisor) {
+            (None, None) => write!(f, "bare metal"),
+            (None, Some(hv)) => write!(f, "{} virtual machine", hv.name()),
+            (Some(c), None) => write!(f, "{} container", c.name()),
+            (Some(c), Some(hv)) => write!(f, "{} container on {}", c.name(), hv.name()),
+        }
+    }
+}
+impl Display for Battery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let capacity = self.capacity.map_or("".to_string(), |c| format!("{}%", c));
+        let capacity = match self.capacity {
+            Some(c) if c < 20 => capacity.red().bold(),
+            Some(c) if c < 50 => capacity.yellow().bold(),
+            _ => capacity.green().bold(),
+        };
+        let time = match (self.time_to_empty, self.time_to_full) {
+            (Some(secs), _) => format!("  {} left", utils::conv_t(secs as f64)),
+            (_, Some(secs)) => format!("  {} until full", utils::conv_t(secs as f64)),
+            _ => "".to_string(),
+        };
+        write!(
+            f,
+            "{} {}  {}{}{}{}",
+            self.name,
+            capacity,
+            self.status,
+            time,
+            self.health.map_or("".to_string(), |h| format!("  health {:.0}%", h)),
+            self.cycle_count.map_or("".to_string(), |c| format!("  cycles {}", c)),
+        )
+    }
+}
+impl Display for AcAdapter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} {}", self.name, if self.online { "online".green().bold() } else { "offline".normal() })
+    }
+}
+impl Display for CpuTopology {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let first = self.processors.first();
+        let show = |n: Option<u32>| n.map_or("".to_string(), |n| n.to_string());
+        let mut caches = String::new();
+        for cache in &self.caches {
+            let name = match &cache.kind[..] {
+                "Data" => format!("L{}d", cache.level),
+                "Instruction" => format!("L{}i", cache.level),
+                _ => format!("L{}", cache.level),
+            };
+            caches.push_str(&format!("{} {}  ", name, utils::conv_b(cache.size)));
+        }
+        let mut processors = String::new();
+        for p in &self.processors {
+            processors.push_str(&p.to_string());
+        }
+        write!(
+            f,
+            "
+│ CPU TOPOLOGY: 
+│   ├─{}──────────────────────────────────
+│   │     VENDOR:        {}
+│   │     FAMILY:        {}
+│   │     MODEL:         {}
+│   │     STEPPING:      {}
+│   │     MICROCODE:     {}
+│   │     SOCKETS:       {}
+│   │     CORES:         {}
+│   │     THREADS:       {}
+│   │     CACHES:        {}
+│   │     FLAGS:         {}
+│   │     PROCESSORS: {}",
+            first.map_or("", |p| &p.model_name).red().bold(),
+            first.map_or("", |p| &p.vendor),
+            show(first.and_then(|p| p.family)),
+            show(first.and_then(|p| p.model)),
+            show(first.and_then(|p| p.stepping)),
+            first.and_then(|p| p.microcode.as_deref()).unwrap_or(""),
+            self.sockets,
+            self.cores,
+            self.threads,
+            caches.trim_end(),
+            first.map_or("".to_string(), |p| p.flags.join(" ")),
+            processors
+        )
+    }
+}
+impl Display for Processor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "\n│   │         ├─{}  SOCKET: {}  CORE: {}  {}",
+            format!("cpu{}", self.id).blue().bold(),
+            self.physical_id,
+            self.core_id,
+            self.mhz.map_or("".to_string(), |mhz| format!("{:.2} MHz", mhz))
+        )
+    }
+}
+impl Display for CpuFreq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut cores = String::new();
+        for core in &self.cores {
+            cores.push_str(&core.to_string());
+        }
+        write!(
+            f,
+            "
+│ CPU FREQUENCY: 
+│   │     BOOST:         {}
+│   │     CORES: {}",
+            show_boost(self.boost),
+            cores
         )
     }
 }
+impl Display for CoreFreq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "\n│   │         ├─{}  {} MHz  [{} - {} MHz]  {}  {}  {}",
+            format!("cpu{}", self.cpu).blue().bold(),
+            show_khz(self.cur).bold(),
+            show_khz(self.min),
+            show_khz(self.max),
+            self.driver.as_deref().unwrap_or(""),
+            self.governor.as_deref().unwrap_or("").green().bold(),
+            self.energy_performance_preference.as_deref().unwrap_or("")
+        )
+    }
+}
+impl Display for PciDevices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut s = String::new();
+        for dev in &self.devices {
+            s.push_str(&dev.to_string());
+        }
+        write!(f, "\n│ PCI DEVICES: {}", s)
+    }
+}
+impl Display for PciDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let link = |speed: &Option<String>, width: Option<u8>| match (speed, width) {
+            (Some(speed), Some(width)) => format!("{} x{}", speed, width),
+            _ => "".to_string(),
+        };
+        let subsystem = match (self.subsystem_vendor_id, self.subsystem_device_id) {
+            (Some(v), Some(d)) => format!("{:04x}:{:04x} {}", v, d, self.subsystem_name.as_deref().unwrap_or("")),
+            _ => "".to_string(),
+        };
+        write!(
+            f,
+            "
+│   ├─{}──────────────────────────────────
+│   │     NAME:          {}
+│   │     CLASS:         {} [{:06x}]
+│   │     ID:            {:04x}:{:04x}
+│   │     SUBSYSTEM:     {}
+│   │     DRIVER:        {}
+│   │     NUMA NODE:     {}
+│   │     LINK:          {}
+│   │     MAX LINK:      {}",
+            self.address.red().bold(),
+            self.name().bold(),
+            self.class_name.as_deref().unwrap_or(""),
+            self.class,
+            self.vendor_id,
+            self.device_id,
+            subsystem.trim_end(),
+            self.driver.as_deref().unwrap_or(""),
+            self.numa_node.map_or("".to_string(), |n| n.to_string()),
+            link(&self.link_speed, self.link_width),
+            link(&self.max_link_speed, self.max_link_width)
+        )
+    }
+}
+impl Display for UsbDevices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut s = String::new();
+        for dev in &self.devices {
+            s.push_str(&dev.to_string());
+        }
+        write!(f, "\n│ USB DEVICES: {}", s)
+    }
+}
+impl UsbDevice {
+    fn fmt_tree(&self, depth: usize, out: &mut String) {
+        out.push_str(&format!(
+            "\n│   {}├─{} {:04x}:{:04x} {} {}  {}  [{}]",
+            "│   ".repeat(depth),
+            self.name.cyan().bold(),
+            self.vendor_id,
+            self.product_id,
+            self.manufacturer.as_deref().unwrap_or(""),
+            self.product.as_deref().unwrap_or("").bold(),
+            self.speed.as_ref().map_or("".to_string(), |speed| format!("{}M", speed)),
+            self.drivers.join(", ")
+        ));
+        for child in &self.children {
+            child.fmt_tree(depth + 1, out);
+        }
+    }
+}
+impl Display for UsbDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut s = String::new();
+        self.fmt_tree(0, &mut s);
+        write!(f, "{}", s)
+    }
+}
 impl Display for NetworkDevices {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let mut s = String::new();
@@ -170,13 +498,13 @@ impl Display for LogVolume {
         )
     }
 }
-impl Display for Temperatures {
+impl Display for Hwmon {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let mut s = String::new();
-        for dev in &self.temp_devices {
+        for dev in &self.devices {
             s.push_str(&dev.to_string());
         }
-        write!(f, "\n│ TEMPERATURES: {}", s)
+        write!(f, "\n│ SENSORS: {}", s)
     }
 }
 impl Display for DeviceSensors {
@@ -189,14 +517,236 @@ impl Display for DeviceSensors {
             f,
             "
 │   ├─{}──────────────────────────────────
-│   │     SENSORS: {}",
+│   │     SENSORS: {}{}",
             self.name.red().bold(),
-            temps
+            temps,
+            self.error.as_ref().map_or("".normal(), |e| format!("\n│   │     ERROR: {}", e).red())
         )
     }
 }
 impl Display for Sensor {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "\n│   │         ├─{} {}°C", self.name.green().bold(), self.temp)
+        let unit = self.kind.unit();
+        let mut limits = vec![];
+        if let Some(min) = self.min {
+            limits.push(format!("min = {}{}", min, unit));
+        }
+        if let Some(max) = self.max {
+            limits.push(format!("max = {}{}", max, unit));
+        }
+        if let Some(crit) = self.crit {
+            limits.push(format!("crit = {}{}", crit, unit));
+        }
+        if let Some(e) = &self.error {
+            return write!(f, "\n│   │         ├─{} {}", self.name.green().bold(), e.red());
+        }
+        let ratio = match self.kind {
+            SensorKind::Temperature => self.crit_ratio(),
+            _ => None,
+        };
+        write!(
+            f,
+            "\n│   │         ├─{} {}  {}{}",
+            self.name.green().bold(),
+            colour_ratio(format!("{}{}", self.value, unit), ratio),
+            if limits.is_empty() { "".to_string() } else { format!("({})", limits.join(", ")) },
+            if self.in_alarm() { "  ALARM".red().bold() } else { "".normal() }
+        )
+    }
+}
+impl Display for ThermalZones {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut s = String::new();
+        for zone in &self.zones {
+            s.push_str(&zone.to_string());
+        }
+        write!(f, "\n│ THERMAL ZONES: {}", s)
+    }
+}
+impl Display for ThermalZone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut trips = String::new();
+        for trip in &self.trip_points {
+            trips.push_str(&trip.to_string());
+        }
+        let mut cdevs = String::new();
+        for cdev in &self.cooling_devices {
+            cdevs.push_str(&cdev.to_string());
+        }
+        write!(
+            f,
+            "
+│   ├─{} {}──────────────────────────────────
+│   │     TEMP:          {}
+│   │     MODE:          {}
+│   │     POLICY:        {}
+│   │     TRIP POINTS: {}
+│   │     COOLING DEVICES: {}",
+            self.name.red().bold(),
+            self.kind,
+            colour_ratio(format!("{}°C", self.temp), self.crit_ratio()),
+            self.mode.as_deref().unwrap_or(""),
+            self.policy.as_deref().unwrap_or(""),
+            trips,
+            cdevs
+        )
+    }
+}
+impl Display for TripPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "\n│   │         ├─{} {}°C", self.kind.blue().bold(), self.temp)
+    }
+}
+impl Display for CoolingDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "\n│   │         ├─{} {}  STATE: {}/{}",
+            self.name.blue().bold(),
+            self.kind,
+            self.cur_state,
+            self.max_state
+        )
+    }
+}
+
+impl Display for NumaNodes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut s = String::new();
+        for node in &self.nodes {
+            s.push_str(&node.to_string());
+        }
+        write!(f, "\n│ NUMA NODES: {}", s)
+    }
+}
+impl Display for NumaNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let cpus = self.cpus.iter().map(u32::to_string).collect::<Vec<String>>().join(",");
+        let distances = self.distances.iter().map(u32::to_string).collect::<Vec<String>>().join(" ");
+        write!(
+            f,
+            "
+│   ├─{}──────────────────────────────────
+│   │     CPUS:        {}
+│   │     MEMORY:      {} used of {}
+│   │     FREE:        {}
+│   │     FILE/ANON:   {} / {}
+│   │     HUGEPAGES:   {} free of {}
+│   │     DISTANCES:   {}",
+            format!("node{}", self.id).red().bold(),
+            cpus,
+            colour_ratio(utils::conv_b(self.mem_used), self.mem_ratio()),
+            utils::conv_b(self.mem_total),
+            utils::conv_b(self.mem_free),
+            utils::conv_b(self.file_pages),
+            utils::conv_b(self.anon_pages),
+            self.hugepages_free,
+            self.hugepages_total,
+            distances
+        )
+    }
+}
+
+// Lists processes with memory on each node, a process spread over nodes pays for remote access
+pub(crate) fn show_numa_placement(placement: &[NumaPlacement]) -> String {
+    let mut out = "\n│ NUMA PLACEMENT: ".to_string();
+    for p in placement {
+        let nodes = p.nodes.iter().map(|(n, b)| format!("N{} {}", n, utils::conv_b(*b))).collect::<Vec<String>>();
+        let preferred = match p.preferred_node() {
+            Some((node, share)) => format!("  LOCAL: N{} {:.0}%", node, share * 100.),
+            None => "".to_string(),
+        };
+        out.push_str(&format!(
+            "\n│   ├─{} {}  {}{}",
+            p.pid.to_string().blue().bold(),
+            p.name,
+            nodes.join("  "),
+            preferred
+        ));
+    }
+    out
+}
+
+// Lists interrupts that fired, with the cpu handling most of them so imbalance stands out
+pub(crate) fn show_interrupts(title: &str, interrupts: &Interrupts) -> String {
+    let mut out = format!("\n│ {}: ", title);
+    for irq in interrupts.interrupts.iter().filter(|i| i.total() > 0) {
+        let counts = irq.counts.iter().map(|c| *c as f64).collect::<Vec<f64>>();
+        let busiest = match busiest_cpu(&interrupts.cpus, &counts) {
+            // a single cpu handling everything is only worth flagging when there are others
+            Some((cpu, share)) => colour_ratio(
+                format!("CPU{} {:.0}%", cpu, share * 100.),
+                (interrupts.cpus.len() > 1).then_some(share as f32),
+            ),
+            None => "".normal(),
+        };
+        let affinity = irq.affinity.iter().map(u32::to_string).collect::<Vec<String>>().join(",");
+        out.push_str(&format!(
+            "\n│   ├─{}{}  TOTAL: {}  BUSIEST: {}{}",
+            irq.irq.blue().bold(),
+            if irq.devices.is_empty() { "".to_string() } else { format!(" {}", irq.devices.join(", ")) },
+            irq.total(),
+            busiest,
+            if affinity.is_empty() { "".to_string() } else { format!("  AFFINITY: {}", affinity) }
+        ));
+    }
+    out
+}
+
+pub(crate) fn ps_table(ps: &[ps::Process], uptime: f64) -> String {
+    let mut out = format!("{:>7} {:<10} S {:>10} {:>5} {:>9} {:>4}  CMD", "PID", "USER", "RSS", "CPU%", "TIME", "OOM")
+        .bold()
+        .to_string();
+    for p in ps {
+        let time = p.cpu_time() as u64;
+        out.push_str(&format!(
+            "\n{:>7} {:<10} {} {:>10} {:>5.1} {:>9} {:>4}  {}",
+            p.pid,
+            p.user(),
+            show_state(p.state),
+            utils::conv_b(p.resident()),
+            p.cpu_usage(uptime),
+            format!("{}:{:02}:{:02}", time / 3600, time / 60 % 60, time % 60),
+            p.oom_score,
+            show_cmd(p)
+        ));
+    }
+    out
+}
+
+impl Display for watch::ProcessEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let p = self.process();
+        let event = match self {
+            watch::ProcessEvent::Started(_) => "started".green(),
+            watch::ProcessEvent::Exited(_) => "exited".red(),
+            watch::ProcessEvent::Changed { .. } => "changed".yellow(),
+        };
+        write!(f, "{:<7} {:>7} {:<10} {} {}", event, p.pid, p.user(), show_state(p.state), show_cmd(p))
+    }
+}
+
+// Kernel threads have no command line and are shown in brackets like `ps` does
+fn show_cmd(p: &ps::Process) -> String {
+    if p.cmd.is_empty() {
+        format!("[{}]", p.name)
+    } else {
+        p.cmd.replace(char::is_control, " ")
+    }
+}
+
+fn show_state(state: ps::ProcessState) -> &'static str {
+    match state {
+        ps::ProcessState::Running => "R",
+        ps::ProcessState::Sleeping => "S",
+        ps::ProcessState::Waiting => "D",
+        ps::ProcessState::Zombie => "Z",
+        ps::ProcessState::Stopped => "T",
+        ps::ProcessState::TracingStop => "t",
+        ps::ProcessState::Dead => "X",
+        ps::ProcessState::Wakekill => "K",
+        ps::ProcessState::Waking => "W",
+        ps::ProcessState::Parked => "P",
+        ps::ProcessState::Unknown => "?",
     }
 }
@@ -0,0 +1,114 @@
+use super::*;
+
+/// Line of `/proc/interrupts` or `/proc/softirqs`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Interrupt {
+    /// Irq number, or name like `LOC` for architecture specific interrupts and `NET_RX` for softirqs
+    pub irq: String,
+    /// Counts for each cpu in order of `Interrupts::cpus`
+    pub counts: Vec<u64>,
+    /// Interrupt controller like `IO-APIC` or `PCI-MSIX-0000:00:01.0`
+    pub chip: Option<String>,
+    /// Hardware irq number and trigger type like `5-edge`
+    pub hwirq: Option<String>,
+    /// Devices sharing the irq, or description for named interrupts
+    pub devices: Vec<String>,
+    /// Cpus the irq may be delivered to from `smp_affinity_list`
+    pub affinity: Vec<u32>,
+    /// Cpus the irq is actually delivered to, if the kernel reports it
+    pub effective_affinity: Vec<u32>,
+}
+impl Interrupt {
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Interrupts {
+    /// Ids of online cpus from the header, offline ones are missing
+    pub cpus: Vec<u32>,
+    pub interrupts: Vec<Interrupt>,
+}
+impl Interrupts {
+    pub fn get(&self, irq: &str) -> Option<&Interrupt> {
+        self.interrupts.iter().find(|i| i.irq == irq)
+    }
+
+    /// Returns per second rates of interrupts since `prev` was read `elapsed` ago
+    pub fn rates(&self, prev: &Interrupts, elapsed: Duration) -> InterruptRates {
+        let secs = elapsed.as_secs_f64();
+        let rates = self
+            .interrupts
+            .iter()
+            .map(|irq| {
+                let before = prev.get(&irq.irq).map_or(&[][..], |p| &p.counts[..]);
+                let per_cpu = irq
+                    .counts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, now)| {
+                        let delta = now.saturating_sub(before.get(i).copied().unwrap_or(0));
+                        if secs > 0. {
+                            delta as f64 / secs
+                        } else {
+                            0.
+                        }
+                    })
+                    .collect();
+                InterruptRate { irq: irq.irq.clone(), devices: irq.devices.clone(), per_cpu }
+            })
+            .collect();
+        InterruptRates { cpus: self.cpus.clone(), rates }
+    }
+}
+impl IntoIterator for Interrupts {
+    type Item = Interrupt;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.interrupts.into_iter()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct InterruptRate {
+    pub irq: String,
+    pub devices: Vec<String>,
+    /// Interrupts per second for each cpu
+    pub per_cpu: Vec<f64>,
+}
+impl InterruptRate {
+    pub fn total(&self) -> f64 {
+        self.per_cpu.iter().sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct InterruptRates {
+    pub cpus: Vec<u32>,
+    pub rates: Vec<InterruptRate>,
+}
+impl InterruptRates {
+    /// Returns interrupts per second handled by each cpu
+    pub fn per_cpu(&self) -> Vec<f64> {
+        let mut totals = vec![0.; self.cpus.len()];
+        for rate in &self.rates {
+            for (total, r) in totals.iter_mut().zip(&rate.per_cpu) {
+                *total += r;
+            }
+        }
+        totals
+    }
+}
+
+/// Returns the cpu handling most of the `counts` and its share of the total, from 0 to 1.
+/// A share close to 1 on a busy irq means a single cpu does all the work.
+pub fn busiest_cpu(cpus: &[u32], counts: &[f64]) -> Option<(u32, f64)> {
+    let total: f64 = counts.iter().sum();
+    if total <= 0. {
+        return None;
+    }
+    let (i, max) = counts.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+    Some((*cpus.get(i)?, max / total))
+}
@@ -1,16 +1,44 @@
+pub mod cgroup;
+pub mod cpu;
 mod display;
+pub mod dmi;
+pub mod irq;
 pub mod net;
+pub mod numa;
 pub mod opt;
+pub mod os;
 pub mod out;
+pub mod pci;
+pub mod power;
 pub mod procfs;
 pub mod ps;
+pub mod security;
 pub mod sensor;
 pub mod storage;
+#[cfg(test)]
 mod tests;
+pub mod usb;
+pub mod users;
 mod utils;
+pub mod virt;
+pub mod vmstat;
+pub mod watch;
+use self::cgroup::*;
+use self::cpu::*;
+use self::dmi::*;
+use self::irq::*;
 use self::net::*;
+use self::numa::*;
+use self::os::*;
+use self::pci::*;
+use self::power::*;
+use self::security::*;
 use self::sensor::*;
 use self::storage::*;
+use self::usb::*;
+use self::users::*;
+use self::virt::*;
+use self::vmstat::*;
 use anyhow::{anyhow, Result};
 use async_std::fs::read_to_string;
 use regex::Regex;
@@ -49,27 +77,66 @@ pub enum SysProperty {
     StorDev,
     StorMounts,
     SysBlockDev,
+    Hwmon,
+    #[deprecated(note = "renamed to `Hwmon`")]
     Temperature,
+    Thermal,
+    PowerSupply,
+    PciDevices,
+    UsbDevices,
+    Dmi,
+    EtcOsRelease,
+    UsrOsRelease,
+    LsbRelease,
     Route,
     FibTrie,
     IfInet6,
+    LoadAvg,
+    SysCpu,
+    Cgroup,
+    VmStat,
+    Stat,
+    Interrupts,
+    SoftIrqs,
+    Irq,
+    NumaNodes,
+    MountInfo,
 }
 impl SysProperty {
     pub fn path(self) -> &'static Path {
         match self {
-            SysProperty::Hostname => &Path::new("/proc/sys/kernel/hostname"),
-            SysProperty::OsRelease => &Path::new("/proc/sys/kernel/osrelease"),
-            SysProperty::Uptime => &Path::new("/proc/uptime"),
-            SysProperty::Mem => &Path::new("/proc/meminfo"),
-            SysProperty::NetDev => &Path::new("/proc/net/dev"),
-            SysProperty::StorDev => &Path::new("/proc/partitions"),
-            SysProperty::StorMounts => &Path::new("/proc/mounts"),
-            SysProperty::SysBlockDev => &Path::new("/sys/block/*"),
-            SysProperty::CpuInfo => &Path::new("/proc/cpuinfo"),
-            SysProperty::Temperature => &Path::new("/sys/class/hwmon"),
-            SysProperty::Route => &Path::new("/proc/net/route"),
-            SysProperty::FibTrie => &Path::new("/proc/net/fib_trie"),
-            SysProperty::IfInet6 => &Path::new("/proc/net/if_inet6"),
+            SysProperty::Hostname => Path::new("/proc/sys/kernel/hostname"),
+            SysProperty::OsRelease => Path::new("/proc/sys/kernel/osrelease"),
+            SysProperty::Uptime => Path::new("/proc/uptime"),
+            SysProperty::Mem => Path::new("/proc/meminfo"),
+            SysProperty::NetDev => Path::new("/proc/net/dev"),
+            SysProperty::StorDev => Path::new("/proc/partitions"),
+            SysProperty::StorMounts => Path::new("/proc/mounts"),
+            SysProperty::SysBlockDev => Path::new("/sys/block/*"),
+            SysProperty::CpuInfo => Path::new("/proc/cpuinfo"),
+            #[allow(deprecated)]
+            SysProperty::Hwmon | SysProperty::Temperature => Path::new("/sys/class/hwmon"),
+            SysProperty::Thermal => Path::new("/sys/class/thermal"),
+            SysProperty::PowerSupply => Path::new("/sys/class/power_supply"),
+            SysProperty::PciDevices => Path::new("/sys/bus/pci/devices"),
+            SysProperty::UsbDevices => Path::new("/sys/bus/usb/devices"),
+            SysProperty::Dmi => Path::new("/sys/class/dmi/id"),
+            SysProperty::EtcOsRelease => Path::new("/etc/os-release"),
+            SysProperty::UsrOsRelease => Path::new("/usr/lib/os-release"),
+            SysProperty::LsbRelease => Path::new("/etc/lsb-release"),
+            SysProperty::Route => Path::new("/proc/net/route"),
+            SysProperty::FibTrie => Path::new("/proc/net/fib_trie"),
+            SysProperty::IfInet6 => Path::new("/proc/net/if_inet6"),
+            SysProperty::LoadAvg => Path::new("/proc/loadavg"),
+            SysProperty::SysCpu => Path::new("/sys/devices/system/cpu"),
+            SysProperty::Cgroup => Path::new("/sys/fs/cgroup"),
+            SysProperty::VmStat => Path::new("/proc/vmstat"),
+            SysProperty::Stat => Path::new("/proc/stat"),
+            SysProperty::Interrupts => Path::new("/proc/interrupts"),
+            SysProperty::SoftIrqs => Path::new("/proc/softirqs"),
+            SysProperty::Irq => Path::new("/proc/irq"),
+            SysProperty::NumaNodes => Path::new("/sys/devices/system/node"),
+            SysProperty::MountInfo => Path::new("/proc/self/mountinfo"),
         }
     }
 }
@@ -85,36 +152,119 @@ pub enum Memory {
 pub struct PcInfo {
     hostname: String,
     kernel_version: String,
+    pub os: OsInfo,
+    pub dmi: Dmi,
+    pub environment: Environment,
     uptime: f64,
     cpu: String,
     cpu_clock: f32,
+    cpu_cores: usize,
+    pub load: LoadAvg,
+    pub cpu_topology: CpuTopology,
+    pub cpu_freq: CpuFreq,
     memory: u64,
     free_memory: u64,
     swap: u64,
     free_swap: u64,
+    pub limits: ResourceLimits,
+    pub power: PowerSupplies,
     pub network_dev: NetworkDevices,
     pub storage_dev: Storages,
     pub vgs: VolGroups,
-    graphics_card: String,
-    pub temps: Temperatures,
+    graphics_cards: Vec<String>,
+    pub pci: PciDevices,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb: Option<UsbDevices>,
+    #[serde(alias = "temps")]
+    pub sensors: Hwmon,
+    pub thermal: ThermalZones,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<KernelRates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interrupts: Option<Interrupts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub softirqs: Option<Interrupts>,
+    pub numa: NumaNodes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa_placement: Option<Vec<NumaPlacement>>,
 }
+
+// Parts of PcInfo that are expensive to collect and only shown with their flag
+struct Sections {
+    summary: bool,
+    devices: bool,
+    interrupts: bool,
+    numa: bool,
+}
+
 impl PcInfo {
+    /// Collects everything including devices, interrupts, numa placement and kernel rates
     pub async fn new() -> PcInfo {
+        PcInfo::collect(Sections { summary: true, devices: true, interrupts: true, numa: true }).await
+    }
+
+    /// Collects only what is displayed with `opts`, other optional sections are left as `None`.
+    /// Kernel rates are sampled only for the summary, which takes 250ms.
+    pub async fn from_opts(opts: &opt::Opt) -> PcInfo {
+        PcInfo::collect(Sections {
+            summary: !opts.quiet,
+            devices: opts.devices,
+            interrupts: opts.interrupts,
+            numa: opts.numa,
+        })
+        .await
+    }
+
+    async fn collect(sections: Sections) -> PcInfo {
+        // summary lists graphics cards found among pci devices
+        let pci =
+            if sections.summary || sections.devices { handle(procfs::pci_devices().await) } else { Default::default() };
+        let dmi = procfs::dmi().await;
+        let memory = handle(procfs::mem(Memory::MemTotal).await);
+        let cpu_cores = handle(procfs::total_cpu_cores().await);
+        let numa = handle(procfs::numa_nodes().await);
         PcInfo {
             hostname: handle(procfs::hostname().await),
-            kernel_version: handle(procfs::os_release().await),
+            kernel_version: handle(procfs::kernel_release().await),
+            os: handle(procfs::os_info().await),
+            environment: procfs::environment(&dmi).await,
+            dmi,
             uptime: handle(procfs::uptime().await),
             cpu: handle(procfs::cpu_info().await),
             cpu_clock: handle(procfs::cpu_clock().await),
-            memory: handle(procfs::mem(Memory::MemTotal).await),
+            cpu_cores,
+            load: handle(procfs::loadavg().await),
+            cpu_topology: handle(procfs::cpu_topology().await),
+            cpu_freq: handle(procfs::cpu_freq().await),
+            limits: handle(procfs::resource_limits(memory, cpu_cores).await),
+            memory,
             free_memory: handle(procfs::mem(Memory::MemFree).await),
             swap: handle(procfs::mem(Memory::SwapTotal).await),
             free_swap: handle(procfs::mem(Memory::SwapFree).await),
+            power: handle(procfs::power_supplies().await),
             network_dev: handle(procfs::network_devs().await),
             storage_dev: handle(procfs::storage_devices().await),
             vgs: handle(procfs::vgs().await),
-            graphics_card: handle(procfs::graphics_card().await),
-            temps: handle(procfs::temperatures().await),
+            graphics_cards: pci.gpus().iter().map(|gpu| gpu.name()).collect(),
+            pci,
+            usb: if sections.devices { handle(procfs::usb_devices().await.map(Some)) } else { None },
+            sensors: handle(procfs::hwmon().await),
+            thermal: handle(procfs::thermal_zones().await),
+            interrupts: if sections.interrupts { handle(procfs::interrupts().await.map(Some)) } else { None },
+            softirqs: if sections.interrupts { handle(procfs::softirqs().await.map(Some)) } else { None },
+            // with a single node all memory is local so placement tells nothing
+            numa_placement: if sections.numa && numa.is_numa() {
+                handle(procfs::numa_placement(10).await.map(Some))
+            } else {
+                None
+            },
+            numa,
+            // sampled after collecting everything else so that rustop's own reads don't count
+            kernel: if sections.summary {
+                handle(procfs::kernel_rates(Duration::from_millis(250)).await.map(Some))
+            } else {
+                None
+            },
         }
     }
 }
@@ -30,9 +30,9 @@ impl NetworkDevice {
         let mut current_rx;
         let mut current_tx;
         let mut secs = interval.as_secs();
+        let re = Regex::new(r"([\d\w]*):\s*(\d*)\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*(\d*)")?;
         loop {
             let net_dev = read_to_string(SysProperty::NetDev.path()).await?;
-            let re = Regex::new(r"([\d\w]*):\s*(\d*)\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*(\d*)")?;
             for network_dev in re.captures_iter(&net_dev) {
                 if self.name == network_dev[1] {
                     if secs == interval.as_secs() {
@@ -0,0 +1,86 @@
+use super::*;
+use std::collections::BTreeMap;
+
+/// Memory node from `/sys/devices/system/node/nodeN`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<u32>,
+    /// Memory in bytes
+    pub mem_total: u64,
+    pub mem_free: u64,
+    pub mem_used: u64,
+    pub file_pages: u64,
+    pub anon_pages: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    /// Relative distance to every node in order of ids, 10 means local
+    pub distances: Vec<u32>,
+}
+impl NumaNode {
+    pub fn mem_ratio(&self) -> Option<f32> {
+        if self.mem_total == 0 {
+            None
+        } else {
+            Some(self.mem_used as f32 / self.mem_total as f32)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct NumaNodes {
+    pub nodes: Vec<NumaNode>,
+}
+impl NumaNodes {
+    /// Returns true if memory is split between more than one node
+    pub fn is_numa(&self) -> bool {
+        self.nodes.len() > 1
+    }
+
+    pub fn get(&self, id: u32) -> Option<&NumaNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    pub fn distance(&self, from: u32, to: u32) -> Option<u32> {
+        let i = self.nodes.iter().position(|n| n.id == to)?;
+        self.get(from)?.distances.get(i).copied()
+    }
+
+    /// Returns the node the cpu belongs to
+    pub fn node_of_cpu(&self, cpu: u32) -> Option<u32> {
+        self.nodes.iter().find(|n| n.cpus.contains(&cpu)).map(|n| n.id)
+    }
+}
+impl IntoIterator for NumaNodes {
+    type Item = NumaNode;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.into_iter()
+    }
+}
+
+/// Memory of a process split by node it is allocated on, from `/proc/[pid]/numa_maps`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct NumaPlacement {
+    pub pid: u32,
+    pub name: String,
+    /// Bytes allocated on each node
+    pub nodes: BTreeMap<u32, u64>,
+}
+impl NumaPlacement {
+    pub fn total(&self) -> u64 {
+        self.nodes.values().sum()
+    }
+
+    /// Returns the node holding most of the memory and its share of the total, from 0 to 1
+    pub fn preferred_node(&self) -> Option<(u32, f64)> {
+        let total = self.total();
+        let (node, bytes) = self.nodes.iter().max_by_key(|(_, bytes)| **bytes)?;
+        if total == 0 {
+            None
+        } else {
+            Some((*node, *bytes as f64 / total as f64))
+        }
+    }
+}
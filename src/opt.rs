@@ -1,4 +1,8 @@
+use crate::ps::{ProcessFilter, ProcessSort, ProcessState, Signal};
+use crate::utils::{parse_byte_size, parse_seconds};
+use anyhow::Result;
 use std::path::PathBuf;
+use std::time::Duration;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -16,19 +20,31 @@ pub struct Opt {
     /// Prints output in YAML format
     #[structopt(short, long)]
     pub yaml: bool,
+    /// Adds info about cpu topology and frequency scaling
+    #[structopt(short, long)]
+    pub cpu: bool,
+    /// Adds info about PCI and USB devices
+    #[structopt(short, long)]
+    pub devices: bool,
     /// Adds info about storage
     #[structopt(short, long)]
     pub storage: bool,
     /// Adds info about network interfaces
     #[structopt(short, long)]
     pub network: bool,
-    /// Adds info about sensors temperatures
-    #[structopt(short, long)]
-    pub temps: bool,
+    /// Adds info about hardware sensors and thermal zones
+    #[structopt(short = "t", long = "sensors", alias = "temps")]
+    pub sensors: bool,
     /// Adds info about Volume Groups and Logical Volumes
     #[structopt(short = "g", long = "volume-group")]
     pub vgs: bool,
-    /// Limits displayed info to specified flags only, like ['-s', '-n', '-t', '-g']
+    /// Adds info about interrupts and softirqs distribution across cpus
+    #[structopt(short, long)]
+    pub interrupts: bool,
+    /// Adds info about numa nodes and memory placement of processes
+    #[structopt(short = "m", long)]
+    pub numa: bool,
+    /// Limits displayed info to specified flags only, like ['-c', '-d', '-s', '-n', '-t', '-g', '-i', '-m']
     #[structopt(short, long)]
     pub quiet: bool,
     #[structopt(subcommand)]
@@ -37,19 +53,40 @@ pub struct Opt {
 
 static AVAILABLE_OPTIONS: &str = "available options:
 - hostname
+- os
 - kernel
+- dmi
+- environment
+- cgroup
 - uptime
 - cpu
 - cpuclock
+- cpufreq
+- governor
+- topology
+- load
 - memory
 - fmemory
+- numa
+- numamaps
 - swap
 - fswap
+- vmstat
+- rates
+- interrupts
+- softirqs
+- irqrates
+- limits
+- battery
 - network
 - storage
 - vgs
 - graphics
-- temperatures";
+- pci
+- usb
+- temperatures
+- sensors
+- thermal";
 
 #[derive(StructOpt)]
 pub enum OptSubcommands {
@@ -57,4 +94,125 @@ pub enum OptSubcommands {
         #[structopt(help = AVAILABLE_OPTIONS)]
         property: String,
     },
+    /// Lists processes matching all given filters
+    Ps(PsOpt),
+    /// Sends signal to processes matching all given filters
+    Kill(KillOpt),
+    /// Prints processes starting, exiting and changing until interrupted
+    Watch(WatchOpt),
+}
+
+/// Process filters shared by `ps` and `kill`
+#[derive(StructOpt)]
+pub struct FilterOpt {
+    /// Regex matched against process name
+    #[structopt(long)]
+    pub name: Option<String>,
+    /// Regex matched against full command line
+    #[structopt(long)]
+    pub cmd: Option<String>,
+    /// User name or uid
+    #[structopt(short, long)]
+    pub user: Option<String>,
+    /// State letter like 'R' or name like 'sleeping'
+    #[structopt(long)]
+    pub state: Option<ProcessState>,
+    /// Parent pid
+    #[structopt(long)]
+    pub ppid: Option<u32>,
+    /// Minimum resident memory like '512K', '1.5G' or '100m'
+    #[structopt(long, parse(try_from_str = parse_byte_size))]
+    pub min_rss: Option<u64>,
+    /// Minimum cpu usage in percent
+    #[structopt(long)]
+    pub min_cpu: Option<f64>,
+    /// Cgroup path like '/system.slice/docker.service', includes child cgroups
+    #[structopt(long)]
+    pub cgroup: Option<String>,
+    /// Only processes with any effective, permitted or ambient capabilities
+    #[structopt(long)]
+    pub privileged: bool,
+}
+impl FilterOpt {
+    /// Returns true if no filter was given
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.cmd.is_none()
+            && self.user.is_none()
+            && self.state.is_none()
+            && self.ppid.is_none()
+            && self.min_rss.is_none()
+            && self.min_cpu.is_none()
+            && self.cgroup.is_none()
+            && !self.privileged
+    }
+
+    pub fn filter(&self) -> Result<ProcessFilter> {
+        let mut filter = ProcessFilter::new();
+        if let Some(name) = &self.name {
+            filter = filter.name(name)?;
+        }
+        if let Some(cmd) = &self.cmd {
+            filter = filter.cmd(cmd)?;
+        }
+        if let Some(user) = &self.user {
+            filter = filter.user(user);
+        }
+        if let Some(state) = self.state {
+            filter = filter.state(state);
+        }
+        if let Some(ppid) = self.ppid {
+            filter = filter.ppid(ppid);
+        }
+        if let Some(rss) = self.min_rss {
+            filter = filter.min_rss(rss);
+        }
+        if let Some(cpu) = self.min_cpu {
+            filter = filter.min_cpu(cpu);
+        }
+        if let Some(cgroup) = &self.cgroup {
+            filter = filter.cgroup(cgroup);
+        }
+        if self.privileged {
+            filter = filter.privileged();
+        }
+        Ok(filter)
+    }
+}
+
+#[derive(StructOpt)]
+pub struct PsOpt {
+    #[structopt(flatten)]
+    pub filter: FilterOpt,
+    /// Sorts by one of pid, name, user, rss, cpu, time, start, oom
+    #[structopt(long, default_value = "pid")]
+    pub sort: ProcessSort,
+    /// Shows at most this many processes
+    #[structopt(long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(StructOpt)]
+pub struct KillOpt {
+    #[structopt(flatten)]
+    pub filter: FilterOpt,
+    /// Signal name or number like 'HUP' or '9'
+    #[structopt(long, default_value = "TERM")]
+    pub signal: Signal,
+    /// Sends SIGKILL to processes still running after this many seconds, only with TERM or INT
+    #[structopt(long, parse(try_from_str = parse_seconds))]
+    pub timeout: Option<Duration>,
+    /// Only lists processes that would be signalled
+    #[structopt(long)]
+    pub dry_run: bool,
+}
+
+#[derive(StructOpt)]
+pub struct WatchOpt {
+    #[structopt(flatten)]
+    pub filter: FilterOpt,
+    /// Milliseconds between process snapshots, shorter living processes are missed unless
+    /// the kernel proc connector can be used, which needs root
+    #[structopt(long, default_value = "500")]
+    pub interval: u64,
 }
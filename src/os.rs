@@ -0,0 +1,25 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct OsInfo {
+    /// `NAME` like `Arch Linux`
+    pub name: String,
+    /// `ID` like `arch`
+    pub id: String,
+    /// `ID_LIKE` - ids of distributions this one is derived from
+    pub id_like: Vec<String>,
+    pub version_id: Option<String>,
+    pub pretty_name: String,
+    /// Machine hardware name like `x86_64`
+    pub architecture: String,
+    /// Init system running as PID 1
+    pub init: String,
+    /// Seconds since epoch when the system was booted
+    pub boot_time: Option<u64>,
+}
+impl OsInfo {
+    /// Returns true if the distribution is `id` or is derived from it
+    pub fn is_like(&self, id: &str) -> bool {
+        self.id == id || self.id_like.iter().any(|like| like == id)
+    }
+}
@@ -1,5 +1,7 @@
-use self::opt::Opt;
+use self::opt::{KillOpt, Opt, PsOpt, WatchOpt};
+use self::watch::ProcessWatcher;
 use super::*;
+use async_std::prelude::*;
 use serde_json::json;
 
 pub fn json_out(p: &PcInfo, opts: &Opt) -> Result<String> {
@@ -12,11 +14,20 @@ pub fn json_out(p: &PcInfo, opts: &Opt) -> Result<String> {
         }
     } else {
         let mut j = json!({});
+        if opts.cpu {
+            j["cpu_topology"] = json!(&p.cpu_topology);
+            j["cpu_freq"] = json!(&p.cpu_freq);
+        }
+        if opts.devices {
+            j["pci"] = json!(&p.pci);
+            j["usb"] = json!(&p.usb);
+        }
         if opts.network {
             j["network_dev"] = json!(&p.network_dev);
         }
-        if opts.temps {
-            j["temps"] = json!(&p.temps);
+        if opts.sensors {
+            j["sensors"] = json!(&p.sensors);
+            j["thermal"] = json!(&p.thermal);
         }
         if opts.storage {
             j["storage"] = json!(&p.storage_dev);
@@ -24,6 +35,14 @@ pub fn json_out(p: &PcInfo, opts: &Opt) -> Result<String> {
         if opts.vgs {
             j["vgs"] = json!(&p.vgs);
         }
+        if opts.interrupts {
+            j["interrupts"] = json!(&p.interrupts);
+            j["softirqs"] = json!(&p.softirqs);
+        }
+        if opts.numa {
+            j["numa"] = json!(&p.numa);
+            j["numa_placement"] = json!(&p.numa_placement);
+        }
         if opts.prettyjson {
             out.push_str(&serde_json::to_string_pretty(&j)?);
         } else {
@@ -38,11 +57,20 @@ pub fn yaml_out(p: &PcInfo, opts: &Opt) -> Result<String> {
     if !opts.quiet {
         out.push_str(&serde_yaml::to_string(&p)?);
     } else {
+        if opts.cpu {
+            out.push_str(&serde_yaml::to_string(&p.cpu_topology)?);
+            out.push_str(&serde_yaml::to_string(&p.cpu_freq)?);
+        }
+        if opts.devices {
+            out.push_str(&serde_yaml::to_string(&p.pci)?);
+            out.push_str(&serde_yaml::to_string(&p.usb)?);
+        }
         if opts.network {
             out.push_str(&serde_yaml::to_string(&p.network_dev)?);
         }
-        if opts.temps {
-            out.push_str(&serde_yaml::to_string(&p.temps)?);
+        if opts.sensors {
+            out.push_str(&serde_yaml::to_string(&p.sensors)?);
+            out.push_str(&serde_yaml::to_string(&p.thermal)?);
         }
         if opts.storage {
             out.push_str(&serde_yaml::to_string(&p.storage_dev)?);
@@ -50,6 +78,14 @@ pub fn yaml_out(p: &PcInfo, opts: &Opt) -> Result<String> {
         if opts.vgs {
             out.push_str(&serde_yaml::to_string(&p.vgs)?);
         }
+        if opts.interrupts {
+            out.push_str(&serde_yaml::to_string(&p.interrupts)?);
+            out.push_str(&serde_yaml::to_string(&p.softirqs)?);
+        }
+        if opts.numa {
+            out.push_str(&serde_yaml::to_string(&p.numa)?);
+            out.push_str(&serde_yaml::to_string(&p.numa_placement)?);
+        }
     }
     Ok(out)
 }
@@ -59,11 +95,22 @@ pub fn normal_out(p: &PcInfo, opts: &Opt) -> String {
     if !opts.quiet {
         out.push_str(&p.to_string());
     }
+    if opts.cpu {
+        out.push_str(&p.cpu_topology.to_string());
+        out.push_str(&p.cpu_freq.to_string());
+    }
+    if opts.devices {
+        out.push_str(&p.pci.to_string());
+        if let Some(usb) = &p.usb {
+            out.push_str(&usb.to_string());
+        }
+    }
     if opts.network {
         out.push_str(&p.network_dev.to_string());
     }
-    if opts.temps {
-        out.push_str(&p.temps.to_string());
+    if opts.sensors {
+        out.push_str(&p.sensors.to_string());
+        out.push_str(&p.thermal.to_string());
     }
     if opts.storage {
         out.push_str(&p.storage_dev.to_string());
@@ -71,26 +118,152 @@ pub fn normal_out(p: &PcInfo, opts: &Opt) -> String {
     if opts.vgs {
         out.push_str(&p.vgs.to_string());
     }
-    out.push_str(&ps::ps_tree());
+    if opts.interrupts {
+        if let Some(interrupts) = &p.interrupts {
+            out.push_str(&display::show_interrupts("INTERRUPTS", interrupts));
+        }
+        if let Some(softirqs) = &p.softirqs {
+            out.push_str(&display::show_interrupts("SOFTIRQS", softirqs));
+        }
+    }
+    if opts.numa {
+        out.push_str(&p.numa.to_string());
+        if let Some(placement) = p.numa_placement.as_ref().filter(|placement| !placement.is_empty()) {
+            out.push_str(&display::show_numa_placement(placement));
+        }
+    }
     out
 }
 
+pub fn ps_out(ps_opts: &PsOpt, opts: &Opt) -> Result<String> {
+    let uptime = procfs::_uptime(&fs::read_to_string(SysProperty::Uptime.path())?);
+    let mut ps = ps_opts.filter.filter()?.processes()?;
+    ps_opts.sort.sort(&mut ps, uptime);
+    ps.truncate(ps_opts.limit.unwrap_or(ps.len()));
+    if opts.prettyjson {
+        Ok(serde_json::to_string_pretty(&ps)?)
+    } else if opts.json {
+        Ok(serde_json::to_string(&ps)?)
+    } else if opts.yaml {
+        Ok(serde_yaml::to_string(&ps)?)
+    } else {
+        Ok(display::ps_table(&ps, uptime))
+    }
+}
+
+pub fn kill_out(kill_opts: &KillOpt) -> Result<String> {
+    if kill_opts.filter.is_empty() {
+        return Err(anyhow!("refusing to signal all processes, specify at least one filter"));
+    }
+    // a reload or pause signal followed by SIGKILL would kill a healthy daemon
+    if kill_opts.timeout.is_some() && !kill_opts.signal.is_termination() {
+        return Err(anyhow!("--timeout escalates to SIGKILL and can only be used with TERM or INT"));
+    }
+    let own = std::process::id();
+    let ps = kill_opts.filter.filter()?.processes()?.into_iter().filter(|p| p.pid != own).collect::<Vec<_>>();
+    if kill_opts.dry_run {
+        return Ok(ps
+            .iter()
+            .map(|p| format!("would send {} to {} {}", kill_opts.signal.name(), p.pid, p.name))
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
+    let mut out = Vec::new();
+    let mut signalled = Vec::new();
+    for p in &ps {
+        match p.signal(kill_opts.signal) {
+            Ok(()) => signalled.push(p),
+            Err(e) => out.push(format!("failed to send {} to {} {} - {}", kill_opts.signal.name(), p.pid, p.name, e)),
+        }
+    }
+    // all processes share one deadline instead of waiting for each in turn
+    let deadline = kill_opts.timeout.map(|t| std::time::Instant::now() + t);
+    for p in signalled {
+        let action = match deadline {
+            Some(deadline) if !p.wait(deadline.saturating_duration_since(std::time::Instant::now())) => {
+                match p.kill() {
+                    Ok(()) => "killed",
+                    Err(_) if !p.is_running() => "exited",
+                    Err(e) => {
+                        out.push(format!("failed to kill {} {} - {}", p.pid, p.name, e));
+                        continue;
+                    }
+                }
+            }
+            Some(_) => "exited",
+            None => "signalled",
+        };
+        out.push(format!("{} {} {}", action, p.pid, p.name));
+    }
+    Ok(out.join("\n"))
+}
+
+pub async fn watch_out(watch_opts: &WatchOpt, opts: &Opt) -> Result<()> {
+    let watcher = ProcessWatcher::new(Duration::from_millis(watch_opts.interval))?;
+    let mut events = watcher.filter(watch_opts.filter.filter()?).into_stream();
+    while let Some(event) = events.next().await {
+        let event = event?;
+        if opts.json || opts.prettyjson {
+            println!("{}", serde_json::to_string(&event)?);
+        } else {
+            println!("{}  {}", utils::conv_date(utils::unix_now()), event);
+        }
+    }
+    Ok(())
+}
+
 pub async fn get_property(property: &str) -> Result<()> {
-    match &property[..] {
+    match property {
         "hostname" => println!("{}", procfs::hostname().await?),
-        "kernel" => println!("{}", procfs::os_release().await?),
+        "kernel" => println!("{}", procfs::kernel_release().await?),
+        "os" => println!("{}", serde_json::to_string_pretty(&procfs::os_info().await?)?),
+        "dmi" => println!("{}", serde_json::to_string_pretty(&procfs::dmi().await)?),
+        "environment" => {
+            println!("{}", serde_json::to_string_pretty(&procfs::environment(&procfs::dmi().await).await)?)
+        }
+        "cgroup" => {
+            let stats = ps::Process::new(std::process::id())?.cgroup_stats()?;
+            println!("{}", serde_json::to_string_pretty(&stats)?)
+        }
+        "limits" => {
+            let (memory, cpus) = (procfs::mem(Memory::MemTotal).await?, procfs::total_cpu_cores().await?);
+            println!("{}", serde_json::to_string_pretty(&procfs::resource_limits(memory, cpus).await?)?)
+        }
         "uptime" => println!("{}", procfs::uptime().await?),
         "cpu" => println!("{}", procfs::cpu_info().await?),
         "cpuclock" => println!("{}", procfs::cpu_clock().await?),
+        "cpufreq" => println!("{}", serde_json::to_string_pretty(&procfs::cpu_freq().await?)?),
+        "governor" => println!("{}", procfs::cpu_freq().await?.governors().join(" ")),
+        "topology" => println!("{}", serde_json::to_string_pretty(&procfs::cpu_topology().await?)?),
+        "load" => println!("{}", serde_json::to_string_pretty(&procfs::loadavg().await?)?),
+        "vmstat" => println!("{}", serde_json::to_string_pretty(&procfs::kernel_counters().await?)?),
+        "rates" => println!("{}", serde_json::to_string_pretty(&procfs::kernel_rates(Duration::from_secs(1)).await?)?),
+        "interrupts" => println!("{}", serde_json::to_string_pretty(&procfs::interrupts().await?)?),
+        "softirqs" => println!("{}", serde_json::to_string_pretty(&procfs::softirqs().await?)?),
+        "irqrates" => {
+            let (interrupts, softirqs) = procfs::interrupt_rates(Duration::from_secs(1)).await?;
+            println!("{}", serde_json::to_string_pretty(&json!({ "interrupts": interrupts, "softirqs": softirqs }))?)
+        }
         "memory" => println!("{}", procfs::mem(Memory::MemTotal).await?),
         "fmemory" => println!("{}", procfs::mem(Memory::MemFree).await?),
+        "numa" => println!("{}", serde_json::to_string_pretty(&procfs::numa_nodes().await?)?),
+        "numamaps" => println!("{}", serde_json::to_string_pretty(&procfs::numa_placement(10).await?)?),
         "swap" => println!("{}", procfs::mem(Memory::SwapTotal).await?),
         "fswap" => println!("{}", procfs::mem(Memory::SwapFree).await?),
+        "battery" => println!("{}", serde_json::to_string_pretty(&procfs::power_supplies().await?)?),
         "network" => println!("{}", serde_json::to_string_pretty(&procfs::network_devs().await?)?),
         "storage" => println!("{}", serde_json::to_string_pretty(&procfs::storage_devices().await?)?),
         "vgs" => println!("{}", serde_json::to_string_pretty(&procfs::vgs().await?)?),
-        "graphics" => println!("{}", procfs::graphics_card().await?),
+        "graphics" => {
+            for gpu in procfs::graphics_cards().await? {
+                println!("{}", gpu.name())
+            }
+        }
+        "usb" => println!("{}", serde_json::to_string_pretty(&procfs::usb_devices().await?)?),
+        "pci" => println!("{}", serde_json::to_string_pretty(&procfs::pci_devices().await?)?),
         "temperatures" => println!("{}", serde_json::to_string_pretty(&procfs::temperatures().await?)?),
+        "sensors" => println!("{}", serde_json::to_string_pretty(&procfs::hwmon().await?)?),
+        "thermal" => println!("{}", serde_json::to_string_pretty(&procfs::thermal_zones().await?)?),
         _ => println!("unsupported property"),
     }
     Ok(())
@@ -0,0 +1,145 @@
+use super::*;
+use std::collections::HashMap;
+
+/// Locations of pci.ids database used by common distributions
+pub static PCI_IDS_PATHS: [&str; 3] = ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids", "/usr/share/pci.ids"];
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct PciDevice {
+    /// Domain, bus, slot and function like `0000:09:00.0`
+    pub address: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub subsystem_vendor_id: Option<u16>,
+    pub subsystem_device_id: Option<u16>,
+    /// Class, subclass and programming interface
+    pub class: u32,
+    pub revision: Option<u8>,
+    pub driver: Option<String>,
+    pub numa_node: Option<u32>,
+    pub link_speed: Option<String>,
+    pub link_width: Option<u8>,
+    pub max_link_speed: Option<String>,
+    pub max_link_width: Option<u8>,
+    /// Names resolved from pci.ids if it is available
+    pub vendor_name: Option<String>,
+    pub device_name: Option<String>,
+    pub subsystem_name: Option<String>,
+    pub class_name: Option<String>,
+}
+impl PciDevice {
+    /// Returns true for VGA compatible, 3D and display controllers
+    pub fn is_gpu(&self) -> bool {
+        matches!(self.class >> 8, 0x0300 | 0x0302 | 0x0380)
+    }
+
+    /// Returns vendor and device name, or their ids if names weren't resolved
+    pub fn name(&self) -> String {
+        match (&self.vendor_name, &self.device_name) {
+            (Some(vendor), Some(device)) => format!("{} {}", vendor, device),
+            (Some(vendor), None) => format!("{} {:04x}", vendor, self.device_id),
+            _ => format!("{:04x}:{:04x}", self.vendor_id, self.device_id),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct PciDevices {
+    pub devices: Vec<PciDevice>,
+}
+impl PciDevices {
+    pub fn gpus(&self) -> Vec<&PciDevice> {
+        self.devices.iter().filter(|dev| dev.is_gpu()).collect()
+    }
+}
+
+impl IntoIterator for PciDevices {
+    type Item = PciDevice;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.into_iter()
+    }
+}
+
+#[derive(Debug, Default)]
+struct PciVendor {
+    name: String,
+    devices: HashMap<u16, String>,
+    subsystems: HashMap<(u16, u16, u16), String>,
+}
+
+/// Vendor, device and class names parsed from pci.ids
+#[derive(Debug, Default)]
+pub struct PciIds {
+    vendors: HashMap<u16, PciVendor>,
+    classes: HashMap<u16, String>,
+}
+impl PciIds {
+    pub fn parse(ids: &str) -> PciIds {
+        let mut pci_ids = PciIds::default();
+        let mut vendor: Option<u16> = None;
+        let mut device: Option<u16> = None;
+        let mut class: Option<u8> = None;
+        for line in ids.lines().filter(|l| !l.starts_with('#') && !l.trim().is_empty()) {
+            let depth = line.chars().take_while(|c| *c == '\t').count();
+            let line = line.trim_start_matches('\t');
+            let (id, name) = match line.split_once(' ') {
+                Some((id, name)) => (id, name.trim().to_string()),
+                None => continue,
+            };
+            match depth {
+                0 if id == "C" => {
+                    let (class_id, name) = name.split_once(' ').unwrap_or(("", ""));
+                    class = u8::from_str_radix(class_id, 16).ok();
+                    vendor = None;
+                    if let Some(c) = class {
+                        pci_ids.classes.insert(u16::from(c) << 8 | 0xff, name.trim().to_string());
+                    }
+                }
+                0 => {
+                    vendor = u16::from_str_radix(id, 16).ok();
+                    class = None;
+                    if let Some(v) = vendor {
+                        pci_ids.vendors.insert(v, PciVendor { name, ..Default::default() });
+                    }
+                }
+                1 => {
+                    if let Some(c) = class {
+                        if let Ok(subclass) = u8::from_str_radix(id, 16) {
+                            pci_ids.classes.insert(u16::from(c) << 8 | u16::from(subclass), name);
+                        }
+                    } else if let Some(v) = vendor.and_then(|v| pci_ids.vendors.get_mut(&v)) {
+                        device = u16::from_str_radix(id, 16).ok();
+                        if let Some(d) = device {
+                            v.devices.insert(d, name);
+                        }
+                    }
+                }
+                2 => {
+                    if let (Some(v), Some(d)) = (vendor.and_then(|v| pci_ids.vendors.get_mut(&v)), device) {
+                        let (sub_device, name) = name.split_once(' ').unwrap_or(("", ""));
+                        if let (Ok(sv), Ok(sd)) = (u16::from_str_radix(id, 16), u16::from_str_radix(sub_device, 16)) {
+                            v.subsystems.insert((d, sv, sd), name.trim().to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        pci_ids
+    }
+
+    /// Fills in names of the device
+    pub fn resolve(&self, dev: &mut PciDevice) {
+        if let Some(vendor) = self.vendors.get(&dev.vendor_id) {
+            dev.vendor_name = Some(vendor.name.clone());
+            dev.device_name = vendor.devices.get(&dev.device_id).cloned();
+            if let (Some(sv), Some(sd)) = (dev.subsystem_vendor_id, dev.subsystem_device_id) {
+                dev.subsystem_name = vendor.subsystems.get(&(dev.device_id, sv, sd)).cloned();
+            }
+        }
+        let class = (dev.class >> 8) as u16;
+        dev.class_name = self.classes.get(&class).or_else(|| self.classes.get(&(class | 0xff))).cloned();
+    }
+}
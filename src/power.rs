@@ -0,0 +1,78 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Battery {
+    pub name: String,
+    pub manufacturer: Option<String>,
+    pub model_name: Option<String>,
+    pub technology: Option<String>,
+    /// Charging, Discharging, Full, Not charging or Unknown
+    pub status: String,
+    /// Charge level in percent
+    pub capacity: Option<u8>,
+    /// Energy in µWh
+    pub energy_now: Option<u64>,
+    pub energy_full: Option<u64>,
+    pub energy_full_design: Option<u64>,
+    /// Charge in µAh, reported instead of energy by some batteries
+    pub charge_now: Option<u64>,
+    pub charge_full: Option<u64>,
+    pub charge_full_design: Option<u64>,
+    /// Power draw in µW
+    pub power_now: Option<u64>,
+    /// Current draw in µA
+    pub current_now: Option<u64>,
+    /// Voltage in µV
+    pub voltage_now: Option<u64>,
+    pub cycle_count: Option<u32>,
+    /// Full capacity as percentage of design capacity
+    pub health: Option<f32>,
+    /// Estimated seconds until empty when discharging
+    pub time_to_empty: Option<u64>,
+    /// Estimated seconds until full when charging
+    pub time_to_full: Option<u64>,
+}
+impl Battery {
+    /// Fills in health and time estimates from the raw readings
+    /// if the kernel didn't report them directly
+    pub(crate) fn estimate(&mut self) {
+        // charge and current are used only if the battery doesn't report energy and power
+        let (now, full, design, rate) = if self.energy_now.is_some() {
+            (self.energy_now, self.energy_full, self.energy_full_design, self.power_now)
+        } else {
+            (self.charge_now, self.charge_full, self.charge_full_design, self.current_now)
+        };
+        if let (Some(full), Some(design)) = (full, design) {
+            if design > 0 {
+                self.health = Some(full as f32 * 100. / design as f32);
+            }
+        }
+        if let (Some(now), Some(rate)) = (now, rate.filter(|rate| *rate > 0)) {
+            match &self.status[..] {
+                "Discharging" if self.time_to_empty.is_none() => self.time_to_empty = Some(now * 3600 / rate),
+                "Charging" if self.time_to_full.is_none() => {
+                    self.time_to_full = full.map(|full| full.saturating_sub(now) * 3600 / rate)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct AcAdapter {
+    pub name: String,
+    pub online: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct PowerSupplies {
+    pub batteries: Vec<Battery>,
+    pub adapters: Vec<AcAdapter>,
+}
+impl PowerSupplies {
+    /// Returns true if any AC adapter is plugged in
+    pub fn on_ac(&self) -> bool {
+        self.adapters.iter().any(|ac| ac.online)
+    }
+}
@@ -1,23 +1,193 @@
 use super::*;
 
-pub async fn os_release() -> Result<String> {
+pub async fn kernel_release() -> Result<String> {
     Ok(fs::read_to_string(SysProperty::OsRelease.path())?.trim_end().to_string())
 }
 
+#[deprecated(note = "returns the kernel release, use `kernel_release` or `os_info` for the distribution")]
+pub async fn os_release() -> Result<String> {
+    kernel_release().await
+}
+
+/// Returns distribution info, architecture, init system and boot time. Distribution fields
+/// are left empty on systems without os-release or lsb-release like minimal containers.
+pub async fn os_info() -> Result<OsInfo> {
+    let mut os = match fs::read_to_string(SysProperty::EtcOsRelease.path())
+        .or_else(|_| fs::read_to_string(SysProperty::UsrOsRelease.path()))
+    {
+        Ok(out) => _os_release(&out),
+        Err(_) => fs::read_to_string(SysProperty::LsbRelease.path())
+            .map_or_else(|_| OsInfo::default(), |out| _lsb_release(&out)),
+    };
+    os.architecture = utils::uname_machine();
+    os.init = _init_system(
+        fs::read_to_string("/proc/1/comm").unwrap_or_default().trim(),
+        fs::read_link("/sbin/init").ok().as_ref().and_then(|p| p.file_name()).and_then(|n| n.to_str()),
+        Path::new("/run/systemd/system").exists(),
+    );
+    os.boot_time = uptime().await.ok().map(|uptime| _boot_time(utils::unix_now(), uptime));
+    Ok(os)
+}
+
+// Splits KEY=VALUE lines into pairs, unquoting values
+fn env_pairs(out: &str) -> Vec<(&str, String)> {
+    out.lines()
+        .filter(|l| !l.trim_start().starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim(), v.trim().trim_matches(|c| c == '"' || c == '\'').replace("\\\"", "\"")))
+        .collect()
+}
+
+pub(crate) fn _os_release(out: &str) -> OsInfo {
+    let mut os = OsInfo { name: "Linux".to_string(), id: "linux".to_string(), ..Default::default() };
+    for (key, value) in env_pairs(out) {
+        match key {
+            "NAME" => os.name = value,
+            "ID" => os.id = value,
+            "ID_LIKE" => os.id_like = value.split_whitespace().map(str::to_string).collect(),
+            "VERSION_ID" => os.version_id = Some(value),
+            "PRETTY_NAME" => os.pretty_name = value,
+            _ => {}
+        }
+    }
+    if os.pretty_name.is_empty() {
+        os.pretty_name = os.name.clone();
+    }
+    os
+}
+
+pub(crate) fn _lsb_release(out: &str) -> OsInfo {
+    let mut os = OsInfo::default();
+    for (key, value) in env_pairs(out) {
+        match key {
+            "DISTRIB_ID" => {
+                os.id = value.to_lowercase();
+                os.name = value;
+            }
+            "DISTRIB_RELEASE" => os.version_id = Some(value),
+            "DISTRIB_DESCRIPTION" => os.pretty_name = value,
+            _ => {}
+        }
+    }
+    os
+}
+
+/// Guesses init system from the name of PID 1 and where /sbin/init points to
+pub(crate) fn _init_system(comm: &str, sbin_init: Option<&str>, systemd_booted: bool) -> String {
+    if systemd_booted {
+        return "systemd".to_string();
+    }
+    let name = match comm {
+        "init" => sbin_init.unwrap_or("sysvinit"),
+        name => name,
+    };
+    match name {
+        "openrc-init" => "openrc",
+        "runit" | "runit-init" => "runit",
+        "s6-svscan" | "s6-linux-init" => "s6",
+        "busybox" => "busybox",
+        "init" => "sysvinit",
+        name => name,
+    }
+    .to_string()
+}
+
+pub(crate) fn _boot_time(now: u64, uptime: f64) -> u64 {
+    now.saturating_sub(uptime as u64)
+}
+
 pub async fn hostname() -> Result<String> {
     Ok(fs::read_to_string(SysProperty::Hostname.path())?.trim_end().to_string())
 }
 
+pub async fn dmi() -> Dmi {
+    _dmi(SysProperty::Dmi.path())
+}
+
+pub(crate) fn _dmi(root: &Path) -> Dmi {
+    // firmware fills unset fields with placeholders like "To Be Filled By O.E.M."
+    let read = |name: &str| {
+        fs::read_to_string(root.join(name))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty() && !s.starts_with("To Be Filled") && !s.starts_with("Default string"))
+    };
+    Dmi {
+        sys_vendor: read("sys_vendor"),
+        product_name: read("product_name"),
+        product_version: read("product_version"),
+        product_serial: read("product_serial"),
+        product_uuid: read("product_uuid"),
+        board_vendor: read("board_vendor"),
+        board_name: read("board_name"),
+        board_version: read("board_version"),
+        board_serial: read("board_serial"),
+        bios_vendor: read("bios_vendor"),
+        bios_version: read("bios_version"),
+        bios_date: read("bios_date"),
+        chassis_vendor: read("chassis_vendor"),
+        chassis_type: read("chassis_type").and_then(|t| t.parse::<u8>().ok()).map(|t| chassis_type(t).to_string()),
+        chassis_serial: read("chassis_serial"),
+    }
+}
+
+pub async fn environment(dmi: &Dmi) -> Environment {
+    let cpuinfo = fs::read_to_string(SysProperty::CpuInfo.path()).unwrap_or_default();
+    _environment(Path::new("/"), dmi, &cpuinfo, utils::cpuid_hypervisor().as_deref())
+}
+
+/// Checks sources from the most to the least specific: hypervisor sysfs, cpuid signature,
+/// DMI strings and finally the `hypervisor` cpu flag
+pub(crate) fn _environment(root: &Path, dmi: &Dmi, cpuinfo: &str, cpuid: Option<&str>) -> Environment {
+    let read = |path: &str| fs::read_to_string(root.join(path)).unwrap_or_default();
+    let hypervisor = match read("sys/hypervisor/type").trim() {
+        "xen" => Some(Hypervisor::Xen),
+        _ => None,
+    }
+    .or_else(|| cpuid.and_then(Hypervisor::from_cpuid))
+    .or_else(|| Hypervisor::from_dmi(dmi))
+    .or_else(|| {
+        cpuinfo
+            .lines()
+            .filter(|l| l.starts_with("flags"))
+            .any(|l| l.split_whitespace().any(|flag| flag == "hypervisor"))
+            .then_some(Hypervisor::Unknown)
+    });
+
+    let cgroup = read("proc/1/cgroup");
+    // systemd and lxc export variable `container` to the init process of a container
+    let environ = read("proc/1/environ");
+    let container_var = environ.split('\0').find_map(|var| var.strip_prefix("container="));
+    let container = if cgroup.contains("kubepods") || environ.contains("KUBERNETES_SERVICE_HOST=") {
+        Some(Container::Kubernetes)
+    } else if root.join(".dockerenv").exists() || cgroup.contains("/docker") {
+        Some(Container::Docker)
+    } else if root.join("run/.containerenv").exists() || cgroup.contains("libpod") || container_var == Some("podman") {
+        Some(Container::Podman)
+    } else if cgroup.contains("/lxc") || container_var == Some("lxc") {
+        Some(Container::Lxc)
+    } else {
+        container_var.filter(|name| !name.is_empty()).map(|name| Container::Other(name.to_string()))
+    };
+    Environment { hypervisor, container }
+}
+
+// Sysfs classes are missing when no driver registered into them, like in containers
+fn read_dir_if_exists(root: &Path) -> Result<Option<fs::ReadDir>> {
+    match fs::read_dir(root) {
+        Ok(entries) => Ok(Some(entries)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
 pub async fn uptime() -> Result<f64> {
     let output = fs::read_to_string(SysProperty::Uptime.path())?;
     Ok(_uptime(&output))
 }
 
 pub(crate) fn _uptime(out: &str) -> f64 {
-    match out.split(' ').collect::<Vec<&str>>()[0].parse::<f64>() {
-        Ok(up) => up,
-        Err(_) => 0.0,
-    }
+    out.split(' ').collect::<Vec<&str>>()[0].parse::<f64>().unwrap_or(0.0)
 }
 
 pub async fn cpu_info() -> Result<String> {
@@ -27,7 +197,7 @@ pub async fn cpu_info() -> Result<String> {
 
 pub(crate) fn _cpu_info(out: &str) -> String {
     let re = Regex::new(r"model name\s*: (.*)").unwrap();
-    re.captures(&out).map_or("".to_string(), |x| x[1].to_string())
+    re.captures(out).map_or("".to_string(), |x| x[1].to_string())
 }
 
 pub async fn mem(target: Memory) -> Result<u64> {
@@ -41,7 +211,7 @@ pub(crate) fn _mem(target: Memory, out: &str) -> u64 {
         Memory::MemTotal => Regex::new(r"MemTotal:\s*(\d*)").unwrap(),
         Memory::MemFree => Regex::new(r"MemFree:\s*(\d*)").unwrap(),
     };
-    match re.captures(&out).map(|m| handle(m[1].parse::<u64>())) {
+    match re.captures(out).map(|m| handle(m[1].parse::<u64>())) {
         Some(n) => n * 1024,
         _ => 0,
     }
@@ -54,7 +224,7 @@ pub async fn total_clock_speed() -> Result<f32> {
 
 pub(crate) fn _total_clock_speed(out: &str) -> f32 {
     let re = Regex::new(r"cpu MHz\s*: (.*)").unwrap();
-    re.captures_iter(&out).map(|x| handle(x[1].parse::<f32>())).sum::<f32>()
+    re.captures_iter(out).map(|x| handle(x[1].parse::<f32>())).sum::<f32>()
 }
 
 pub async fn total_cpu_cores() -> Result<usize> {
@@ -63,11 +233,140 @@ pub async fn total_cpu_cores() -> Result<usize> {
 }
 
 pub(crate) fn _total_cpu_cores(out: &str) -> usize {
-    out.rmatches("cpu MHz").count()
+    let re = Regex::new(r"(?m)^processor\s*:").unwrap();
+    re.find_iter(out).count()
 }
 
+/// Returns average clock of all cores in MHz. Uses cpufreq if available
+/// falling back to /proc/cpuinfo
 pub async fn cpu_clock() -> Result<f32> {
-    Ok(total_clock_speed().await? / total_cpu_cores().await? as f32)
+    match cpu_freq().await.ok().and_then(|freq| freq.avg_mhz()) {
+        Some(mhz) => Ok(mhz),
+        None => Ok(total_clock_speed().await? / total_cpu_cores().await? as f32),
+    }
+}
+
+pub async fn cpu_freq() -> Result<CpuFreq> {
+    _cpu_freq(SysProperty::SysCpu.path())
+}
+
+pub(crate) fn _cpu_freq(sys_cpu: &Path) -> Result<CpuFreq> {
+    let read = |p: &Path| fs::read_to_string(p).ok().map(|s| s.trim().to_string());
+    let read_khz = |p: &Path| read(p).and_then(|s| s.parse::<u64>().ok());
+    let mut freq = CpuFreq::default();
+    let re = Regex::new(r"^cpu(\d+)$")?;
+    for entry in fs::read_dir(sys_cpu)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(cpu) = re.captures(&name) {
+            let path = entry.path().join("cpufreq");
+            if !path.exists() {
+                continue;
+            }
+            freq.cores.push(CoreFreq {
+                cpu: handle(cpu[1].parse::<u32>()),
+                cur: read_khz(&path.join("scaling_cur_freq")),
+                min: read_khz(&path.join("scaling_min_freq")),
+                max: read_khz(&path.join("scaling_max_freq")),
+                hw_min: read_khz(&path.join("cpuinfo_min_freq")),
+                hw_max: read_khz(&path.join("cpuinfo_max_freq")),
+                driver: read(&path.join("scaling_driver")),
+                governor: read(&path.join("scaling_governor")),
+                available_governors: read(&path.join("scaling_available_governors"))
+                    .map_or(vec![], |g| g.split_whitespace().map(str::to_string).collect()),
+                energy_performance_preference: read(&path.join("energy_performance_preference")),
+            });
+        }
+    }
+    freq.cores.sort_by_key(|c| c.cpu);
+    // acpi-cpufreq exposes boost directly, intel_pstate exposes it inverted as no_turbo
+    freq.boost = match read(&sys_cpu.join("cpufreq/boost")) {
+        Some(boost) => Some(boost == "1"),
+        None => read(&sys_cpu.join("intel_pstate/no_turbo")).map(|no_turbo| no_turbo == "0"),
+    };
+    Ok(freq)
+}
+
+pub async fn cpu_topology() -> Result<CpuTopology> {
+    let output = fs::read_to_string(SysProperty::CpuInfo.path())?;
+    let mut topology = _cpu_topology(&output);
+    let sys_cpu = SysProperty::SysCpu.path();
+    // /proc/cpuinfo lacks physical id and core id on most non x86 systems
+    for processor in topology.processors.iter_mut() {
+        let path = sys_cpu.join(format!("cpu{}/topology", processor.id));
+        if let Ok(id) = fs::read_to_string(path.join("physical_package_id")) {
+            processor.physical_id = handle(id.trim().parse::<u32>());
+        }
+        if let Ok(id) = fs::read_to_string(path.join("core_id")) {
+            processor.core_id = handle(id.trim().parse::<u32>());
+        }
+    }
+    topology.count();
+    if let Ok(entries) = fs::read_dir(sys_cpu.join("cpu0/cache")) {
+        for entry in entries.flatten().filter(|e| e.file_name().to_string_lossy().starts_with("index")) {
+            let read = |name| fs::read_to_string(entry.path().join(name)).unwrap_or_default();
+            topology.caches.push(CpuCache {
+                level: read("level").trim().parse::<u8>().unwrap_or(0),
+                kind: read("type").trim().to_string(),
+                size: utils::parse_size(&read("size")),
+            });
+        }
+        topology.caches.sort_by(|a, b| (a.level, &a.kind).cmp(&(b.level, &b.kind)));
+    }
+    Ok(topology)
+}
+
+pub(crate) fn _cpu_topology(out: &str) -> CpuTopology {
+    let mut topology = CpuTopology::default();
+    for block in out.split("\n\n") {
+        let mut processor = Processor::default();
+        let mut is_processor = false;
+        let mut core_id = None;
+        for (key, value) in block.lines().filter_map(|l| l.split_once(':')).map(|(k, v)| (k.trim(), v.trim())) {
+            match key {
+                "processor" => {
+                    is_processor = true;
+                    processor.id = handle(value.parse::<u32>());
+                }
+                "vendor_id" | "CPU implementer" => processor.vendor = value.to_string(),
+                "model name" => processor.model_name = value.to_string(),
+                "cpu family" => processor.family = utils::parse_num(value),
+                "model" | "CPU part" => processor.model = utils::parse_num(value),
+                "stepping" | "CPU revision" => processor.stepping = utils::parse_num(value),
+                "microcode" => processor.microcode = Some(value.to_string()),
+                "cache size" => processor.cache_size = Some(utils::parse_size(value)),
+                "physical id" => processor.physical_id = handle(value.parse::<u32>()),
+                "core id" => core_id = value.parse::<u32>().ok(),
+                "cpu MHz" => processor.mhz = value.parse::<f32>().ok(),
+                "flags" | "Features" => processor.flags = value.split_whitespace().map(str::to_string).collect(),
+                _ => {}
+            }
+        }
+        if is_processor {
+            // without a core id every processor is assumed to be a separate core
+            processor.core_id = core_id.unwrap_or(processor.id);
+            topology.processors.push(processor);
+        }
+    }
+    topology.count();
+    topology
+}
+
+pub async fn loadavg() -> Result<LoadAvg> {
+    let output = fs::read_to_string(SysProperty::LoadAvg.path())?;
+    Ok(_loadavg(&output))
+}
+
+pub(crate) fn _loadavg(out: &str) -> LoadAvg {
+    let re = Regex::new(r"^([\d.]+)\s+([\d.]+)\s+([\d.]+)\s+(\d+)/(\d+)\s+(\d+)").unwrap();
+    re.captures(out.trim()).map_or(LoadAvg::default(), |load| LoadAvg {
+        one: handle(load[1].parse::<f32>()),
+        five: handle(load[2].parse::<f32>()),
+        fifteen: handle(load[3].parse::<f32>()),
+        running_tasks: handle(load[4].parse::<u32>()),
+        total_tasks: handle(load[5].parse::<u32>()),
+        last_pid: handle(load[6].parse::<u32>()),
+    })
 }
 
 pub async fn network_devs() -> Result<NetworkDevices> {
@@ -80,13 +379,13 @@ pub async fn network_devs() -> Result<NetworkDevices> {
 pub(crate) fn _network_devs(net_dev: &str, route: &str, fib_trie: &str, if_inet: &str) -> Result<NetworkDevices> {
     let mut devices = vec![];
     let re = Regex::new(r"([\d\w]*):\s*(\d*)\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*\d*\s*(\d*)")?;
-    for network_dev in re.captures_iter(&net_dev) {
+    for network_dev in re.captures_iter(net_dev) {
         devices.push(NetworkDevice {
             name: network_dev[1].to_string(),
             received_bytes: handle(network_dev[2].parse::<u64>()),
             transfered_bytes: handle(network_dev[3].parse::<u64>()),
-            ipv4_addr: _ipv4_addr(&network_dev[1], &route, &fib_trie)?,
-            ipv6_addr: _ipv6_addr(&network_dev[1], &if_inet)?,
+            ipv4_addr: _ipv4_addr(&network_dev[1], route, fib_trie)?,
+            ipv6_addr: _ipv6_addr(&network_dev[1], if_inet)?,
         });
     }
     Ok(NetworkDevices { net_devices: devices })
@@ -102,11 +401,11 @@ pub(crate) fn _storage_devices(stor_dev: &str, stor_mounts: &str) -> Storages {
     let mut devices = Vec::new();
     let re = Regex::new(r"(?m)^\s*(\d*)\s*(\d*)\s*(\d*)\s([\w\d]*)$").unwrap();
     for storage_dev in re
-        .captures_iter(&stor_dev)
+        .captures_iter(stor_dev)
         .filter(|storage_dev| !(storage_dev[4].starts_with("loop") || storage_dev[4].starts_with("ram")))
         .filter(|storage_dev| {
             let stor_dev_re = Regex::new(r"^[a-z]+$").unwrap();
-            stor_dev_re.is_match(&storage_dev[4].to_string())
+            stor_dev_re.is_match(&storage_dev[4])
         })
     {
         devices.push(Storage {
@@ -114,7 +413,7 @@ pub(crate) fn _storage_devices(stor_dev: &str, stor_mounts: &str) -> Storages {
             minor: handle(storage_dev[2].parse::<u16>()),
             size: handle(storage_dev[3].parse::<u64>()) * 1024,
             name: storage_dev[4].to_string(),
-            partitions: _storage_partitions(&storage_dev[4], &stor_dev, &stor_mounts),
+            partitions: _storage_partitions(&storage_dev[4], stor_dev, stor_mounts),
         });
     }
 
@@ -125,18 +424,18 @@ pub(crate) fn _storage_devices(stor_dev: &str, stor_mounts: &str) -> Storages {
 async fn storage_partitions(stor_name: &str) -> Result<Partitions> {
     let stor_dev = fs::read_to_string(SysProperty::StorDev.path())?;
     let stor_mounts = fs::read_to_string(SysProperty::StorMounts.path())?;
-    Ok(_storage_partitions(&stor_name, &stor_dev, &stor_mounts))
+    Ok(_storage_partitions(stor_name, &stor_dev, &stor_mounts))
 }
 
 pub(crate) fn _storage_partitions(stor_name: &str, stor_dev: &str, stor_mounts: &str) -> Partitions {
     let mut partitions = vec![];
     let re = Regex::new(r"(?m)^\s*(\d*)\s*(\d*)\s*(\d*)\s(\w*\d+)$").unwrap();
     let re2 = Regex::new(r"/dev/(\w*)\s(\S*)\s(\S*)").unwrap();
-    for storage_dev in re.captures_iter(&stor_dev).filter(|x| x[4].starts_with(stor_name)) {
+    for storage_dev in re.captures_iter(stor_dev).filter(|x| x[4].starts_with(stor_name)) {
         let mut partition = Partition::default();
         let partition_name = &storage_dev[4];
 
-        for found_partition in re2.captures_iter(&stor_mounts) {
+        for found_partition in re2.captures_iter(stor_mounts) {
             if &found_partition[1] == partition_name {
                 partition.mountpoint = found_partition[2].to_string();
                 partition.filesystem = found_partition[3].to_string();
@@ -165,7 +464,7 @@ pub async fn vgs() -> Result<VolGroups> {
         let re = Regex::new(
             r"(?m)VG Name\s*(.*)\n.*\n\s*Format\s*(.*)$(?:\n.*){3}\s*VG Status\s*(.*)$(?:\n.*){6}$\s*VG Size\s*(\d*)",
         )?;
-        for vg in re.captures_iter(&out) {
+        for vg in re.captures_iter(out) {
             vgs.push(VolGroup {
                 name: vg[1].to_string(),
                 format: vg[2].to_string(),
@@ -186,7 +485,7 @@ async fn lvms(vg_name: String) -> Result<Vec<LogVolume>> {
     let re = Regex::new(
         r"(?m)LV Path\s*(.*)\n\s*LV Name\s*(.*)$\s*VG Name\s*(.*)$(?:\n.*){3}$\s*LV Status\s*(.*)\n.*$\n\s*LV Size\s*(\d*).*$(?:\n.*){5}\s*Block device\s*(\d*):(\d*)$",
     )?;
-    for lvm in re.captures_iter(&out).filter(|lvm| lvm[3] == vg_name) {
+    for lvm in re.captures_iter(out).filter(|lvm| lvm[3] == vg_name) {
         lvms_vec.push(LogVolume {
             name: lvm[2].to_string(),
             path: lvm[1].to_string(),
@@ -201,21 +500,127 @@ async fn lvms(vg_name: String) -> Result<Vec<LogVolume>> {
     Ok(lvms_vec)
 }
 
+#[deprecated(note = "returns only the first one, use `graphics_cards`")]
 pub async fn graphics_card() -> Result<String> {
-    let cmd = Command::new("lspci").output()?;
-    let out = str::from_utf8(&cmd.stdout)?;
-    Ok(_graphics_card(&out))
+    Ok(_graphics_card(&pci_devices().await?))
+}
+
+/// Returns name of the first gpu, empty if there is none
+pub(crate) fn _graphics_card(pci: &PciDevices) -> String {
+    pci.gpus().first().map_or("".to_string(), |gpu| gpu.name())
+}
+
+pub async fn graphics_cards() -> Result<Vec<PciDevice>> {
+    Ok(pci_devices().await?.into_iter().filter(|dev| dev.is_gpu()).collect())
+}
+
+pub async fn pci_devices() -> Result<PciDevices> {
+    let mut devices = _pci_devices(SysProperty::PciDevices.path())?;
+    if let Some(ids) = PCI_IDS_PATHS.iter().find_map(|p| fs::read_to_string(p).ok()) {
+        let ids = PciIds::parse(&ids);
+        devices.devices.iter_mut().for_each(|dev| ids.resolve(dev));
+    }
+    Ok(devices)
+}
+
+pub(crate) fn _pci_devices(root: &Path) -> Result<PciDevices> {
+    let mut devices = vec![];
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        let read = |name: &str| fs::read_to_string(path.join(name)).ok().map(|s| s.trim().to_string());
+        let read_hex = |name: &str| read(name).and_then(|s| utils::parse_num(&s));
+        devices.push(PciDevice {
+            address: path.file_name().map_or("".to_string(), |n| n.to_string_lossy().to_string()),
+            vendor_id: read_hex("vendor").unwrap_or(0) as u16,
+            device_id: read_hex("device").unwrap_or(0) as u16,
+            subsystem_vendor_id: read_hex("subsystem_vendor").map(|id| id as u16),
+            subsystem_device_id: read_hex("subsystem_device").map(|id| id as u16),
+            class: read_hex("class").unwrap_or(0),
+            revision: read_hex("revision").map(|rev| rev as u8),
+            driver: fs::read_link(path.join("driver"))
+                .ok()
+                .and_then(|driver| driver.file_name().map(|n| n.to_string_lossy().to_string())),
+            // -1 if the platform has no NUMA
+            numa_node: read("numa_node").and_then(|node| node.parse::<u32>().ok()),
+            link_speed: read("current_link_speed"),
+            link_width: read("current_link_width").and_then(|w| w.parse::<u8>().ok()),
+            max_link_speed: read("max_link_speed"),
+            max_link_width: read("max_link_width").and_then(|w| w.parse::<u8>().ok()),
+            ..Default::default()
+        });
+    }
+    devices.sort_by(|a, b| a.address.cmp(&b.address));
+    Ok(PciDevices { devices })
+}
+
+pub async fn usb_devices() -> Result<UsbDevices> {
+    _usb_devices(SysProperty::UsbDevices.path())
 }
-pub(crate) fn _graphics_card(out: &str) -> String {
-    let re = Regex::new(r"(?m)VGA compatible controller:\s*(.*)$").unwrap();
-    re.captures(&out).map_or("".to_string(), |vga| vga[1].to_string())
+
+pub(crate) fn _usb_devices(root: &Path) -> Result<UsbDevices> {
+    let link_name =
+        |p: &Path| fs::read_link(p).ok().and_then(|l| l.file_name().map(|n| n.to_string_lossy().to_string()));
+    let mut devices = vec![];
+    let mut interfaces = vec![];
+    // no usb controller at all, common in virtual machines and containers
+    let entries = match read_dir_if_exists(root)? {
+        Some(entries) => entries,
+        None => return Ok(UsbDevices::default()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // interfaces are named like 1-1.2:1.0
+        if name.contains(':') {
+            interfaces.push((name, link_name(&entry.path().join("driver"))));
+            continue;
+        }
+        let path = entry.path();
+        let read = |attr: &str| fs::read_to_string(path.join(attr)).ok().map(|s| s.trim().to_string());
+        let read_hex = |attr: &str| read(attr).and_then(|s| u16::from_str_radix(&s, 16).ok()).unwrap_or(0);
+        // sysfs names start with the bus number, like usb1 or 1-1.2
+        let name_bus = name.strip_prefix("usb").or_else(|| name.split_once('-').map(|(bus, _)| bus));
+        devices.push(UsbDevice {
+            bus: read("busnum")
+                .or_else(|| name_bus.map(str::to_string))
+                .and_then(|n| n.parse::<u32>().ok())
+                .unwrap_or(0),
+            devnum: read("devnum").and_then(|n| n.parse::<u32>().ok()).unwrap_or(0),
+            port: name.split_once('-').map_or("".to_string(), |(_, port)| port.to_string()),
+            vendor_id: read_hex("idVendor"),
+            product_id: read_hex("idProduct"),
+            manufacturer: read("manufacturer"),
+            product: read("product"),
+            serial: read("serial"),
+            speed: read("speed"),
+            drivers: link_name(&path.join("driver")).into_iter().collect(),
+            name,
+            children: vec![],
+        });
+    }
+    interfaces.sort();
+    for (interface, driver) in interfaces {
+        // root hub interfaces are named after port 0 of the bus, like 1-0:1.0
+        let device = match interface.split(':').next().unwrap_or("").split_once('-') {
+            Some((bus, "0")) => format!("usb{}", bus),
+            _ => interface.split(':').next().unwrap_or("").to_string(),
+        };
+        if let (Some(dev), Some(driver)) = (devices.iter_mut().find(|d| d.name == device), driver) {
+            // the device itself is always bound to the generic usb driver
+            dev.drivers.retain(|d| d != "usb");
+            if !dev.drivers.contains(&driver) {
+                dev.drivers.push(driver);
+            }
+        }
+    }
+    Ok(UsbDevices::from_flat(devices))
 }
 
 #[allow(dead_code)]
 async fn ipv4_addr(interface_name: &str) -> Result<Ipv4Addr> {
     let route = fs::read_to_string(SysProperty::Route.path())?;
     let fib_trie = fs::read_to_string(SysProperty::FibTrie.path())?;
-    _ipv4_addr(&interface_name, &route, &fib_trie)
+    _ipv4_addr(interface_name, &route, &fib_trie)
 }
 
 pub(crate) fn _ipv4_addr(interface_name: &str, route: &str, fib_trie: &str) -> Result<Ipv4Addr> {
@@ -225,7 +630,7 @@ pub(crate) fn _ipv4_addr(interface_name: &str, route: &str, fib_trie: &str) -> R
         Ok(Ipv4Addr::LOCALHOST)
     } else {
         let re = Regex::new(r"(?m)^([\d\w]*)\s*([\d\w]*)")?;
-        for dest in re.captures_iter(&route) {
+        for dest in re.captures_iter(route) {
             if &dest[1] == interface_name && &dest[2] != "00000000" {
                 iface_dest = utils::conv_hex_to_ip(&dest[2])?;
             }
@@ -234,11 +639,11 @@ pub(crate) fn _ipv4_addr(interface_name: &str, route: &str, fib_trie: &str) -> R
         let file = fib_trie.split('\n').collect::<Vec<&str>>();
         let re = Regex::new(r"\|--\s+(.*)")?;
         let mut found = false;
-        for (i, line) in (&file).iter().enumerate() {
+        for (i, line) in file.iter().enumerate() {
             if (*line).to_string().contains(&iface_dest) {
                 found = true;
             } else if found && (*line).to_string().contains("/32 host LOCAL") {
-                ip_addr = match re.captures(&file[i - 1]) {
+                ip_addr = match re.captures(file[i - 1]) {
                     Some(n) => Ipv4Addr::from_str(&n[1])?,
                     None => Ipv4Addr::UNSPECIFIED,
                 };
@@ -252,7 +657,7 @@ pub(crate) fn _ipv4_addr(interface_name: &str, route: &str, fib_trie: &str) -> R
 #[allow(dead_code)]
 async fn ipv6_addr(interface_name: &str) -> Result<Ipv6Addr> {
     let output = fs::read_to_string(SysProperty::IfInet6.path())?;
-    _ipv6_addr(&interface_name, &output)
+    _ipv6_addr(interface_name, &output)
 }
 
 pub(crate) fn _ipv6_addr(interface_name: &str, out: &str) -> Result<Ipv6Addr> {
@@ -261,7 +666,7 @@ pub(crate) fn _ipv6_addr(interface_name: &str, out: &str) -> Result<Ipv6Addr> {
     } else {
         let mut ip_addr = Ipv6Addr::UNSPECIFIED;
         let re = Regex::new(r"(?m)^([\d\w]*)\s\d*\s\d*\s\d*\s\d*\s*(.*)$").unwrap();
-        for capture in re.captures_iter(&out) {
+        for capture in re.captures_iter(out) {
             if &capture[2] == interface_name {
                 ip_addr = Ipv6Addr::from_str(&format!(
                     "{}:{}:{}:{}:{}:{}:{}:{}",
@@ -281,39 +686,675 @@ pub(crate) fn _ipv6_addr(interface_name: &str, out: &str) -> Result<Ipv6Addr> {
     }
 }
 
-pub async fn temperatures() -> Result<Temperatures> {
-    // reconsider if this should really return an error if one of the sensors doesn't have a label f.e.
-    let paths = fs::read_dir(SysProperty::Temperature.path())?;
+pub async fn hwmon() -> Result<Hwmon> {
+    _hwmon(SysProperty::Hwmon.path())
+}
+
+pub async fn temperatures() -> Result<Hwmon> {
+    Ok(hwmon().await?.only(SensorKind::Temperature))
+}
+
+pub(crate) fn _hwmon(root: &Path) -> Result<Hwmon> {
     let mut devices: Vec<DeviceSensors> = vec![];
-    let re = Regex::new(r"temp[\d]+_input")?;
-    for dir_entry in paths {
-        let mut sensor_count = 0;
-        let path = dir_entry?.path();
-        let mut dev = DeviceSensors::default();
-        let mut dev_temps: Vec<Sensor> = vec![];
-        dev.name = fs::read_to_string(path.join("name"))?.trim().to_string();
-        for temp_file in fs::read_dir(&path)? {
-            if re.is_match(&temp_file?.path().to_str().unwrap()) {
-                sensor_count += 1;
-            }
-        }
-        for i in 1..=sensor_count {
-            dev_temps.push(sensor(&path, i).await?);
+    let re = Regex::new(r"^(temp|in|fan|curr|power|energy)(\d+)_(?:input|average)$")?;
+    // hwmon class is missing when no sensor driver is loaded
+    let entries = match read_dir_if_exists(root)? {
+        Some(entries) => entries,
+        None => return Ok(Hwmon::default()),
+    };
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let mut dev = DeviceSensors { name: hwmon_name(&path), sensors: vec![], error: None };
+        match sensor_indices(&path, &re) {
+            Ok(found) => dev.sensors = found.into_iter().map(|(kind, i)| hwmon_sensor(&path, kind, i)).collect(),
+            Err(e) => dev.error = Some(e.to_string()),
         }
-        dev.sensors = dev_temps;
         devices.push(dev);
     }
-    Ok(Temperatures { temp_devices: devices })
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Hwmon { devices })
+}
+
+/// Returns name of hwmon device. Not every driver provides a name attribute
+/// so this falls back to the underlying device and its driver.
+fn hwmon_name(path: &Path) -> String {
+    let read = |p: &Path| fs::read_to_string(p).ok().map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let link = |p: &Path| fs::read_link(p).ok().and_then(|t| t.file_name().map(|n| n.to_string_lossy().to_string()));
+    read(&path.join("name"))
+        .or_else(|| read(&path.join("device/name")))
+        .or_else(|| link(&path.join("device/driver")))
+        .or_else(|| link(&path.join("device")))
+        .unwrap_or_else(|| path.file_name().map_or("".to_string(), |n| n.to_string_lossy().to_string()))
+}
+
+/// Returns sorted (kind, index) of every sensor present in hwmon device directory.
+/// Sensor indices are often sparse, f.e. a device might only have temp2 and temp5.
+fn sensor_indices(path: &Path, re: &Regex) -> Result<Vec<(SensorKind, u32)>> {
+    let mut found: Vec<(SensorKind, u32)> = vec![];
+    for file in fs::read_dir(path)? {
+        let file_name = file?.file_name();
+        if let Some(m) = re.captures(&file_name.to_string_lossy()) {
+            if let (Some(kind), Ok(i)) = (SensorKind::from_prefix(&m[1]), m[2].parse::<u32>()) {
+                found.push((kind, i));
+            }
+        }
+    }
+    found.sort_unstable();
+    found.dedup();
+    Ok(found)
 }
 
+#[deprecated(note = "reads only temperatures, use `hwmon_sensor`")]
 pub async fn sensor<P: AsRef<Path>>(path: P, i: i32) -> Result<Sensor> {
-    let mut sensor = Sensor::default();
-    sensor.name = fs::read_to_string(path.as_ref().join(format!("temp{}_label", i)))
-        .unwrap_or_else(|_| "".to_string())
-        .trim()
-        .to_string();
-    sensor.temp =
-        handle(fs::read_to_string(path.as_ref().join(format!("temp{}_input", i)))?.trim().parse::<f32>()) / 1000.;
-
-    Ok(sensor)
+    use std::convert::TryFrom;
+    let i = u32::try_from(i)?;
+    let sensor = hwmon_sensor(path, SensorKind::Temperature, i);
+    match sensor.error {
+        Some(e) => Err(anyhow!("failed to read temp{} - {}", i, e)),
+        None => Ok(sensor),
+    }
+}
+
+/// Reads a single hwmon sensor. Failure to read the value is recorded in
+/// `Sensor::error` so that other sensors of the device can still be reported.
+pub fn hwmon_sensor<P: AsRef<Path>>(path: P, kind: SensorKind, i: u32) -> Sensor {
+    let attr = |suffix: &str| path.as_ref().join(format!("{}{}_{}", kind.prefix(), i, suffix));
+    let read_value = |suffix: &str| -> Option<f32> {
+        fs::read_to_string(attr(suffix)).ok()?.trim().parse::<f32>().ok().map(|v| v / kind.scale())
+    };
+    let read_flag = |suffix: &str| fs::read_to_string(attr(suffix)).ok().map(|flag| flag.trim() == "1");
+    // power sensors may only expose an averaged reading
+    let value = match fs::read_to_string(attr("input")) {
+        Ok(value) => Ok(value),
+        Err(e) => fs::read_to_string(attr("average")).map_err(|_| e),
+    };
+    let (value, error) = match value.map(|v| v.trim().parse::<f32>()) {
+        Ok(Ok(value)) => (value / kind.scale(), None),
+        Ok(Err(e)) => (0., Some(e.to_string())),
+        Err(e) => (0., Some(e.to_string())),
+    };
+
+    Sensor {
+        name: fs::read_to_string(attr("label"))
+            .map(|label| label.trim().to_string())
+            .unwrap_or_else(|_| format!("{}{}", kind.prefix(), i)),
+        kind,
+        value,
+        min: read_value("min"),
+        max: read_value("max"),
+        crit: read_value("crit"),
+        alarm: read_flag("alarm"),
+        min_alarm: read_flag("min_alarm"),
+        max_alarm: read_flag("max_alarm"),
+        crit_alarm: read_flag("crit_alarm"),
+        error,
+    }
+}
+
+pub async fn thermal_zones() -> Result<ThermalZones> {
+    _thermal_zones(SysProperty::Thermal.path())
+}
+
+pub(crate) fn _thermal_zones(root: &Path) -> Result<ThermalZones> {
+    let read = |p: &Path| fs::read_to_string(p).ok().map(|s| s.trim().to_string());
+    let read_temp = |p: &Path| read(p).and_then(|t| t.parse::<f32>().ok()).map(|t| t / 1000.);
+    let trip_re = Regex::new(r"^trip_point_(\d+)_type$")?;
+    let cdev_re = Regex::new(r"^cdev(\d+)$")?;
+    let mut zones = vec![];
+    let entries = match read_dir_if_exists(root)? {
+        Some(entries) => entries,
+        None => return Ok(ThermalZones::default()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with("thermal_zone") {
+            continue;
+        }
+        let path = entry.path();
+        let mut zone = ThermalZone {
+            name,
+            kind: read(&path.join("type")).unwrap_or_default(),
+            temp: read_temp(&path.join("temp")).unwrap_or(0.),
+            mode: read(&path.join("mode")),
+            policy: read(&path.join("policy")),
+            ..Default::default()
+        };
+        let mut trips = vec![];
+        let mut cdevs = vec![];
+        for file in fs::read_dir(&path)? {
+            let file_name = file?.file_name().to_string_lossy().to_string();
+            if let Some(m) = trip_re.captures(&file_name) {
+                trips.push(handle(m[1].parse::<u32>()));
+            } else if let Some(m) = cdev_re.captures(&file_name) {
+                cdevs.push(handle(m[1].parse::<u32>()));
+            }
+        }
+        trips.sort_unstable();
+        cdevs.sort_unstable();
+        for i in trips {
+            zone.trip_points.push(TripPoint {
+                kind: read(&path.join(format!("trip_point_{}_type", i))).unwrap_or_default(),
+                temp: read_temp(&path.join(format!("trip_point_{}_temp", i))).unwrap_or(0.),
+                hyst: read_temp(&path.join(format!("trip_point_{}_hyst", i))),
+            });
+        }
+        // cdevN is a link to the bound cooling device in /sys/class/thermal
+        for i in cdevs {
+            let cdev = path.join(format!("cdev{}", i));
+            zone.cooling_devices.push(CoolingDevice {
+                name: fs::read_link(&cdev)
+                    .ok()
+                    .and_then(|target| target.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .unwrap_or_else(|| format!("cdev{}", i)),
+                kind: read(&cdev.join("type")).unwrap_or_default(),
+                cur_state: read(&cdev.join("cur_state")).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0),
+                max_state: read(&cdev.join("max_state")).and_then(|s| s.parse::<u32>().ok()).unwrap_or(0),
+                trip_point: read(&path.join(format!("cdev{}_trip_point", i))).and_then(|s| s.parse::<u32>().ok()),
+            });
+        }
+        zones.push(zone);
+    }
+    zones.sort_by_key(|zone| zone.name.trim_start_matches("thermal_zone").parse::<u32>().unwrap_or(0));
+    Ok(ThermalZones { zones })
+}
+
+pub async fn power_supplies() -> Result<PowerSupplies> {
+    _power_supplies(SysProperty::PowerSupply.path())
+}
+
+pub(crate) fn _power_supplies(root: &Path) -> Result<PowerSupplies> {
+    let mut supplies = PowerSupplies::default();
+    let entries = match read_dir_if_exists(root)? {
+        Some(entries) => entries,
+        None => return Ok(supplies),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let read = |name: &str| fs::read_to_string(path.join(name)).ok().map(|s| s.trim().to_string());
+        let read_num = |name: &str| read(name).and_then(|s| s.parse::<u64>().ok());
+        let name = path.file_name().map_or("".to_string(), |n| n.to_string_lossy().to_string());
+        match read("type").as_deref() {
+            Some("Battery") => {
+                // peripherals like wireless mice report their batteries here too
+                if read("scope").as_deref() == Some("Device") {
+                    continue;
+                }
+                let mut battery = Battery {
+                    name,
+                    manufacturer: read("manufacturer"),
+                    model_name: read("model_name"),
+                    technology: read("technology"),
+                    status: read("status").unwrap_or_else(|| "Unknown".to_string()),
+                    capacity: read("capacity").and_then(|s| s.parse::<u8>().ok()),
+                    energy_now: read_num("energy_now"),
+                    energy_full: read_num("energy_full"),
+                    energy_full_design: read_num("energy_full_design"),
+                    charge_now: read_num("charge_now"),
+                    charge_full: read_num("charge_full"),
+                    charge_full_design: read_num("charge_full_design"),
+                    power_now: read_num("power_now"),
+                    current_now: read_num("current_now"),
+                    voltage_now: read_num("voltage_now"),
+                    cycle_count: read("cycle_count").and_then(|s| s.parse::<u32>().ok()),
+                    time_to_empty: read_num("time_to_empty_now"),
+                    time_to_full: read_num("time_to_full_now"),
+                    ..Default::default()
+                };
+                battery.estimate();
+                supplies.batteries.push(battery);
+            }
+            Some("Mains") | Some("USB") => {
+                supplies.adapters.push(AcAdapter { name, online: read("online").as_deref() == Some("1") })
+            }
+            _ => {}
+        }
+    }
+    supplies.batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    supplies.adapters.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(supplies)
+}
+
+pub(crate) fn _proc_cgroups(out: &str) -> Cgroups {
+    Cgroups(
+        out.lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ':');
+                Some(CgroupEntry {
+                    hierarchy: parts.next()?.parse::<u32>().ok()?,
+                    controllers: parts.next()?.split(',').filter(|c| !c.is_empty()).map(str::to_string).collect(),
+                    path: parts.next()?.to_string(),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Reads stats of cgroup at `path` of the unified hierarchy
+pub async fn cgroup_stats(path: &str) -> CgroupStats {
+    let cgroups = Cgroups(vec![CgroupEntry { path: path.to_string(), ..Default::default() }]);
+    _cgroup_stats(SysProperty::Cgroup.path(), &cgroups.relative_to(&cgroup_mounts()))
+}
+
+/// Returns cgroup hierarchies mounted in rustop's mount namespace, empty if unreadable
+pub(crate) fn cgroup_mounts() -> CgroupMounts {
+    _cgroup_mounts(&fs::read_to_string(SysProperty::MountInfo.path()).unwrap_or_default())
+}
+
+/// Parses cgroup mounts from mountinfo lines like
+/// `36 32 0:32 /docker/4f1a /sys/fs/cgroup/memory rw,relatime - cgroup cgroup rw,memory`
+pub(crate) fn _cgroup_mounts(mountinfo: &str) -> CgroupMounts {
+    CgroupMounts(
+        mountinfo
+            .lines()
+            .filter_map(|line| {
+                let (mount, sb) = line.split_once(" - ")?;
+                let root = mount.split_whitespace().nth(3)?;
+                let mut sb = sb.split_whitespace();
+                let controllers = match (sb.next()?, sb.nth(1)) {
+                    ("cgroup2", _) => vec![],
+                    // superblock options hold the controllers next to rw or ro
+                    ("cgroup", Some(opts)) => {
+                        opts.split(',').filter(|o| *o != "rw" && *o != "ro").map(str::to_string).collect()
+                    }
+                    _ => return None,
+                };
+                Some(CgroupMount { controllers, root: root.to_string() })
+            })
+            .collect(),
+    )
+}
+
+/// Reads stats of process cgroups below `root` mount point. Each controller is read from
+/// its v1 hierarchy if mounted, otherwise from the unified one.
+pub(crate) fn _cgroup_stats(root: &Path, cgroups: &Cgroups) -> CgroupStats {
+    // on hybrid systems the unified hierarchy is mounted in a subdirectory
+    let v2_root = if root.join("cgroup.controllers").exists() { root.to_path_buf() } else { root.join("unified") };
+    let v2_dir = cgroups.v2_path().map(|p| v2_root.join(p.trim_start_matches('/')));
+    let v1_dir = |controller: &str| {
+        cgroups
+            .v1_path(controller)
+            .map(|p| root.join(controller).join(p.trim_start_matches('/')))
+            .filter(|d| d.is_dir())
+    };
+    let read = |dir: &Option<std::path::PathBuf>, name: &str| {
+        dir.as_ref().and_then(|d| fs::read_to_string(d.join(name)).ok()).map(|s| s.trim().to_string())
+    };
+    let num = |s: Option<String>| s.and_then(|s| s.parse::<u64>().ok());
+    // v1 reports no limit as the largest page aligned i64
+    let limit = |s: Option<String>| num(s).filter(|l| *l < 0x7FFF_FFFF_FFFF_F000);
+    let key = |s: &Option<String>, key: &str| {
+        s.as_ref().and_then(|s| s.lines().find_map(|l| l.strip_prefix(key)?.trim().parse::<u64>().ok()))
+    };
+
+    let mut stats = CgroupStats { path: cgroups.path().to_string(), ..Default::default() };
+    match v1_dir("memory") {
+        Some(dir) => {
+            let dir = Some(dir);
+            stats.memory_current = num(read(&dir, "memory.usage_in_bytes"));
+            stats.memory_max = limit(read(&dir, "memory.limit_in_bytes"));
+        }
+        None => {
+            stats.memory_current = num(read(&v2_dir, "memory.current"));
+            stats.memory_max = num(read(&v2_dir, "memory.max"));
+        }
+    }
+    match (v1_dir("cpuacct"), v1_dir("cpu")) {
+        (None, None) => {
+            let cpu_stat = read(&v2_dir, "cpu.stat");
+            stats.cpu_usage_usec = key(&cpu_stat, "usage_usec");
+            stats.cpu_user_usec = key(&cpu_stat, "user_usec");
+            stats.cpu_system_usec = key(&cpu_stat, "system_usec");
+            stats.cpu_max = read(&v2_dir, "cpu.max").and_then(|max| {
+                let (quota, period) = max.split_once(' ')?;
+                Some((quota.parse::<u64>().ok()?, period.parse::<u64>().ok()?))
+            });
+        }
+        (acct, cpu) => {
+            let acct = acct.or_else(|| cpu.clone());
+            stats.cpu_usage_usec = num(read(&acct, "cpuacct.usage")).map(|ns| ns / 1000);
+            stats.cpu_user_usec = num(read(&acct, "cpuacct.usage_user")).map(|ns| ns / 1000);
+            stats.cpu_system_usec = num(read(&acct, "cpuacct.usage_sys")).map(|ns| ns / 1000);
+            // quota is -1 when unlimited
+            let quota = num(read(&cpu, "cpu.cfs_quota_us"));
+            stats.cpu_max = quota.zip(num(read(&cpu, "cpu.cfs_period_us")));
+        }
+    }
+    stats.cpuset = match v1_dir("cpuset") {
+        Some(dir) => {
+            let dir = Some(dir);
+            read(&dir, "cpuset.effective_cpus").or_else(|| read(&dir, "cpuset.cpus"))
+        }
+        None => read(&v2_dir, "cpuset.cpus.effective"),
+    }
+    .map_or(vec![], |list| utils::parse_cpu_list(&list));
+    let pids = v1_dir("pids").map(Some).unwrap_or_else(|| v2_dir.clone());
+    stats.pids_current = num(read(&pids, "pids.current"));
+    stats.pids_max = num(read(&pids, "pids.max"));
+
+    match v1_dir("blkio") {
+        Some(dir) => {
+            let dir = Some(dir);
+            let mut io: Vec<CgroupIo> = vec![];
+            for (file, bytes) in [("blkio.throttle.io_service_bytes", true), ("blkio.throttle.io_serviced", false)] {
+                for line in read(&dir, file).unwrap_or_default().lines() {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    if let [device, op, value] = fields[..] {
+                        let value = value.parse::<u64>().unwrap_or(0);
+                        let pos = match io.iter().position(|i| i.device == device) {
+                            Some(pos) => pos,
+                            None => {
+                                io.push(CgroupIo { device: device.to_string(), ..Default::default() });
+                                io.len() - 1
+                            }
+                        };
+                        match (op, bytes) {
+                            ("Read", true) => io[pos].rbytes = value,
+                            ("Write", true) => io[pos].wbytes = value,
+                            ("Read", false) => io[pos].rios = value,
+                            ("Write", false) => io[pos].wios = value,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            stats.io = io;
+        }
+        None => {
+            for line in read(&v2_dir, "io.stat").unwrap_or_default().lines() {
+                let mut fields = line.split_whitespace();
+                let mut io = CgroupIo { device: fields.next().unwrap_or("").to_string(), ..Default::default() };
+                for (k, v) in fields.filter_map(|f| f.split_once('=')) {
+                    let v = v.parse::<u64>().unwrap_or(0);
+                    match k {
+                        "rbytes" => io.rbytes = v,
+                        "wbytes" => io.wbytes = v,
+                        "rios" => io.rios = v,
+                        "wios" => io.wios = v,
+                        _ => {}
+                    }
+                }
+                stats.io.push(io);
+            }
+        }
+    }
+    stats
+}
+
+pub async fn resource_limits(host_memory: u64, host_cpus: usize) -> Result<ResourceLimits> {
+    let cgroups = _proc_cgroups(&fs::read_to_string("/proc/self/cgroup")?).relative_to(&cgroup_mounts());
+    Ok(_resource_limits(SysProperty::Cgroup.path(), cgroups, host_memory, host_cpus))
+}
+
+/// Finds the lowest limits set on the cgroup or any of its ancestors
+pub(crate) fn _resource_limits(root: &Path, cgroups: Cgroups, host_memory: u64, host_cpus: usize) -> ResourceLimits {
+    let leaf = _cgroup_stats(root, &cgroups);
+    let mut memory = leaf.memory_max;
+    let mut cpus = leaf.cpu_limit();
+    let mut group = cgroups.parent();
+    while let Some(cgroups) = group {
+        let stats = _cgroup_stats(root, &cgroups);
+        memory = memory.into_iter().chain(stats.memory_max).min();
+        cpus = cpus.into_iter().chain(stats.cpu_limit()).reduce(f64::min);
+        group = cgroups.parent();
+    }
+    if !leaf.cpuset.is_empty() {
+        cpus = Some(cpus.map_or(leaf.cpuset.len() as f64, |cpus| cpus.min(leaf.cpuset.len() as f64)));
+    }
+    ResourceLimits { memory: memory.filter(|m| *m < host_memory), cpus: cpus.filter(|c| *c < host_cpus as f64) }
+}
+
+/// Reads namespace links in `ns`. Missing links are unsupported namespace types,
+/// any other failure like a permission error is returned.
+pub(crate) fn _namespaces(ns: &Path) -> Result<Namespaces> {
+    let inode = |name: &str| -> Result<Option<u64>> {
+        let link = match fs::read_link(ns.join(name)) {
+            Ok(link) => link,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(anyhow!("failed to read namespace {} - {}", name, e)),
+        };
+        let link = link.to_string_lossy();
+        Ok(link.split_once(":[").and_then(|(_, inode)| inode.trim_end_matches(']').parse::<u64>().ok()))
+    };
+    Ok(Namespaces {
+        cgroup: inode("cgroup")?,
+        ipc: inode("ipc")?,
+        mnt: inode("mnt")?,
+        net: inode("net")?,
+        pid: inode("pid")?,
+        time: inode("time")?,
+        user: inode("user")?,
+        uts: inode("uts")?,
+    })
+}
+
+pub(crate) fn _capabilities(status: &str) -> Capabilities {
+    let mut caps = Capabilities::default();
+    for (key, value) in status.lines().filter_map(|l| l.split_once(':')) {
+        let value = value.trim();
+        let set = || CapabilitySet(u64::from_str_radix(value, 16).unwrap_or(0));
+        match key {
+            "CapInh" => caps.inheritable = set(),
+            "CapPrm" => caps.permitted = set(),
+            "CapEff" => caps.effective = set(),
+            "CapBnd" => caps.bounding = set(),
+            "CapAmb" => caps.ambient = set(),
+            "NoNewPrivs" => caps.no_new_privs = value == "1",
+            "Seccomp" => {
+                caps.seccomp = match value {
+                    "1" => Seccomp::Strict,
+                    "2" => Seccomp::Filter,
+                    _ => Seccomp::Disabled,
+                }
+            }
+            "Seccomp_filters" => caps.seccomp_filters = value.parse::<u32>().ok(),
+            _ => {}
+        }
+    }
+    caps
+}
+
+pub async fn kernel_counters() -> Result<KernelCounters> {
+    Ok(KernelCounters {
+        vmstat: _vmstat(&fs::read_to_string(SysProperty::VmStat.path())?),
+        stat: _kernel_stat(&fs::read_to_string(SysProperty::Stat.path())?),
+    })
+}
+
+/// Returns kernel activity per second measured over `interval`
+pub async fn kernel_rates(interval: Duration) -> Result<KernelRates> {
+    KernelSampler::new().await?.sample(interval).await
+}
+
+pub(crate) fn _vmstat(out: &str) -> VmStat {
+    let mut vm = VmStat::default();
+    for (key, value) in out.lines().filter_map(|l| l.split_once(' ')) {
+        let value = value.trim().parse::<u64>().unwrap_or(0);
+        match key {
+            "pgfault" => vm.pgfault = value,
+            "pgmajfault" => vm.pgmajfault = value,
+            "pgpgin" => vm.pgpgin = value,
+            "pgpgout" => vm.pgpgout = value,
+            "pswpin" => vm.pswpin = value,
+            "pswpout" => vm.pswpout = value,
+            "oom_kill" => vm.oom_kill = value,
+            "compact_stall" => vm.compact_stall = value,
+            "compact_fail" => vm.compact_fail = value,
+            "compact_success" => vm.compact_success = value,
+            _ => {}
+        }
+    }
+    vm
+}
+
+pub(crate) fn _kernel_stat(out: &str) -> KernelStat {
+    let mut stat = KernelStat::default();
+    for line in out.lines() {
+        let mut fields = line.split_whitespace();
+        // intr and softirq lines list per source counts after the total
+        let (key, value) = match (fields.next(), fields.next().and_then(|v| v.parse::<u64>().ok())) {
+            (Some(key), Some(value)) => (key, value),
+            _ => continue,
+        };
+        match key {
+            "ctxt" => stat.ctxt = value,
+            "intr" => stat.intr = value,
+            "softirq" => stat.softirq = value,
+            "processes" => stat.processes = value,
+            "procs_running" => stat.procs_running = value,
+            "procs_blocked" => stat.procs_blocked = value,
+            "btime" => stat.btime = value,
+            _ => {}
+        }
+    }
+    stat
+}
+
+pub async fn interrupts() -> Result<Interrupts> {
+    let mut interrupts = _interrupts(&fs::read_to_string(SysProperty::Interrupts.path())?);
+    _irq_affinity(SysProperty::Irq.path(), &mut interrupts);
+    Ok(interrupts)
+}
+
+pub async fn softirqs() -> Result<Interrupts> {
+    Ok(_interrupts(&fs::read_to_string(SysProperty::SoftIrqs.path())?))
+}
+
+/// Returns interrupts and softirqs per second measured over `interval`
+pub async fn interrupt_rates(interval: Duration) -> Result<(InterruptRates, InterruptRates)> {
+    let (irqs, soft) = (interrupts().await?, softirqs().await?);
+    let start = std::time::Instant::now();
+    async_std::task::sleep(interval).await;
+    let (irqs_now, soft_now) = (interrupts().await?, softirqs().await?);
+    let elapsed = start.elapsed();
+    Ok((irqs_now.rates(&irqs, elapsed), soft_now.rates(&soft, elapsed)))
+}
+
+/// Parses `/proc/interrupts` and `/proc/softirqs` which share the layout
+pub(crate) fn _interrupts(out: &str) -> Interrupts {
+    let mut lines = out.lines();
+    let cpus = lines
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter_map(|cpu| cpu.strip_prefix("CPU")?.parse::<u32>().ok())
+        .collect::<Vec<u32>>();
+    let mut interrupts = vec![];
+    for line in lines {
+        let (irq, rest) = match line.split_once(':') {
+            Some((irq, rest)) => (irq.trim(), rest),
+            None => continue,
+        };
+        let mut fields = rest.split_whitespace().peekable();
+        let mut counts = vec![];
+        // lines like ERR have a single count regardless of cpus
+        while counts.len() < cpus.len() {
+            match fields.peek().and_then(|c| c.parse::<u64>().ok()) {
+                Some(count) => {
+                    counts.push(count);
+                    fields.next();
+                }
+                None => break,
+            }
+        }
+        let rest = fields.collect::<Vec<&str>>();
+        let mut interrupt = Interrupt { irq: irq.to_string(), counts, ..Default::default() };
+        let description = if irq.parse::<u32>().is_ok() && !rest.is_empty() {
+            interrupt.chip = Some(rest[0].to_string());
+            // older kernels merge trigger type into the chip name like IO-APIC-edge
+            let is_hwirq =
+                |f: &str| ["edge", "level", "fasteoi", "eoi"].iter().any(|t| f.ends_with(&format!("-{}", t)));
+            match (rest.get(1), rest.get(2)) {
+                (Some(hwirq), _) if is_hwirq(hwirq) => {
+                    interrupt.hwirq = Some(hwirq.to_string());
+                    rest[2..].join(" ")
+                }
+                // arm gic prints hwirq and trigger as separate words like `27 Level`
+                (Some(hwirq), Some(&trigger))
+                    if hwirq.parse::<u64>().is_ok() && ["Level", "Edge"].contains(&trigger) =>
+                {
+                    interrupt.hwirq = Some(format!("{} {}", hwirq, trigger));
+                    rest[3..].join(" ")
+                }
+                _ => rest[1..].join(" "),
+            }
+        } else {
+            rest.join(" ")
+        };
+        interrupt.devices = description.split(", ").filter(|d| !d.is_empty()).map(str::to_string).collect();
+        interrupts.push(interrupt);
+    }
+    Interrupts { cpus, interrupts }
+}
+
+/// Reads affinity of numbered irqs from `/proc/irq/N` directories under `root`
+pub(crate) fn _irq_affinity(root: &Path, interrupts: &mut Interrupts) {
+    for irq in interrupts.interrupts.iter_mut().filter(|i| i.irq.parse::<u32>().is_ok()) {
+        let dir = root.join(&irq.irq);
+        let read = |name: &str| fs::read_to_string(dir.join(name)).map_or(vec![], |list| utils::parse_cpu_list(&list));
+        irq.affinity = read("smp_affinity_list");
+        irq.effective_affinity = read("effective_affinity_list");
+    }
+}
+
+pub async fn numa_nodes() -> Result<NumaNodes> {
+    _numa_nodes(SysProperty::NumaNodes.path())
+}
+
+pub(crate) fn _numa_nodes(root: &Path) -> Result<NumaNodes> {
+    let mut nodes = vec![];
+    // kernels built without CONFIG_NUMA have no node directory
+    let entries = match read_dir_if_exists(root)? {
+        Some(entries) => entries,
+        None => return Ok(NumaNodes::default()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let id = match entry.file_name().to_string_lossy().strip_prefix("node").and_then(|id| id.parse::<u32>().ok()) {
+            Some(id) => id,
+            None => continue,
+        };
+        let path = entry.path();
+        let mut node = _node_meminfo(&fs::read_to_string(path.join("meminfo")).unwrap_or_default());
+        node.id = id;
+        node.cpus = fs::read_to_string(path.join("cpulist")).map_or(vec![], |list| utils::parse_cpu_list(&list));
+        node.distances = fs::read_to_string(path.join("distance"))
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|d| d.parse::<u32>().ok())
+            .collect();
+        nodes.push(node);
+    }
+    nodes.sort_unstable_by_key(|n| n.id);
+    Ok(NumaNodes { nodes })
+}
+
+/// Parses node meminfo, where lines look like `Node 0 MemTotal:  5734136 kB`
+pub(crate) fn _node_meminfo(out: &str) -> NumaNode {
+    let mut node = NumaNode::default();
+    for line in out.lines() {
+        let mut fields = line.split_whitespace().skip(2);
+        let key = fields.next().unwrap_or("").trim_end_matches(':');
+        let value = fields.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        // hugepage counts have no unit
+        let bytes = if fields.next() == Some("kB") { value * 1024 } else { value };
+        match key {
+            "MemTotal" => node.mem_total = bytes,
+            "MemFree" => node.mem_free = bytes,
+            "MemUsed" => node.mem_used = bytes,
+            "FilePages" => node.file_pages = bytes,
+            "AnonPages" => node.anon_pages = bytes,
+            "HugePages_Total" => node.hugepages_total = bytes,
+            "HugePages_Free" => node.hugepages_free = bytes,
+            _ => {}
+        }
+    }
+    node
+}
+
+/// Returns numa placement of `limit` processes using the most memory
+pub async fn numa_placement(limit: usize) -> Result<Vec<NumaPlacement>> {
+    let mut ps = ps::tree()?;
+    ps::ProcessSort::Rss.sort(&mut ps, 0.);
+    // kernel threads have no memory and other users' maps need privileges
+    Ok(ps.iter().filter(|p| p.resident > 0).filter_map(|p| p.numa_placement().ok()).take(limit).collect())
 }
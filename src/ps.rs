@@ -2,7 +2,7 @@
 use super::*;
 use std::path::PathBuf;
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
 pub enum ProcessState {
     Running,
     Sleeping,
@@ -14,6 +14,7 @@ pub enum ProcessState {
     Wakekill,
     Waking,
     Parked,
+    #[default]
     Unknown,
 }
 impl From<char> for ProcessState {
@@ -34,13 +35,236 @@ impl From<char> for ProcessState {
         }
     }
 }
-impl Default for ProcessState {
-    fn default() -> ProcessState {
-        ProcessState::Unknown
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Signal {
+    Hup,
+    Int,
+    Quit,
+    Kill,
+    Usr1,
+    Usr2,
+    Term,
+    Cont,
+    Stop,
+}
+impl Signal {
+    pub fn all() -> [Signal; 9] {
+        use self::Signal::*;
+        [Hup, Int, Quit, Kill, Usr1, Usr2, Term, Cont, Stop]
+    }
+
+    /// Returns true for signals asking a process to exit, which may be followed by SIGKILL
+    pub fn is_termination(self) -> bool {
+        self == Signal::Term || self == Signal::Int
+    }
+
+    pub fn number(self) -> libc::c_int {
+        match self {
+            Signal::Hup => libc::SIGHUP,
+            Signal::Int => libc::SIGINT,
+            Signal::Quit => libc::SIGQUIT,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Usr1 => libc::SIGUSR1,
+            Signal::Usr2 => libc::SIGUSR2,
+            Signal::Term => libc::SIGTERM,
+            Signal::Cont => libc::SIGCONT,
+            Signal::Stop => libc::SIGSTOP,
+        }
+    }
+
+    /// Returns name without the `SIG` prefix like `TERM`
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Hup => "HUP",
+            Signal::Int => "INT",
+            Signal::Quit => "QUIT",
+            Signal::Kill => "KILL",
+            Signal::Usr1 => "USR1",
+            Signal::Usr2 => "USR2",
+            Signal::Term => "TERM",
+            Signal::Cont => "CONT",
+            Signal::Stop => "STOP",
+        }
+    }
+}
+impl FromStr for Signal {
+    type Err = anyhow::Error;
+
+    /// Parses signal names like `TERM`, `SIGTERM` or numbers like `15`
+    fn from_str(s: &str) -> Result<Signal> {
+        let upper = s.to_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        Signal::all()
+            .iter()
+            .copied()
+            .find(|sig| sig.name() == name || sig.number().to_string() == name)
+            .ok_or_else(|| anyhow!("unsupported signal {}", s))
+    }
+}
+
+/// Resource limit from `/proc/[pid]/limits`, `None` values are unlimited
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Limit {
+    /// Name like `Max open files`
+    pub name: String,
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+    /// Unit like `files` or `bytes`, `None` for unitless limits like `Max nice priority`
+    pub unit: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Limits(pub Vec<Limit>);
+impl Limits {
+    /// Returns limit by name like `Max open files`
+    pub fn get(&self, name: &str) -> Option<&Limit> {
+        self.0.iter().find(|l| l.name == name)
+    }
+}
+impl IntoIterator for Limits {
+    type Item = Limit;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Memory counters of a mapping or whole process from smaps, in bytes
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct MemoryUsage {
+    pub size: u64,
+    pub rss: u64,
+    /// Proportional set size - shared pages divided between processes sharing them
+    pub pss: u64,
+    pub pss_anon: u64,
+    pub pss_file: u64,
+    pub pss_shmem: u64,
+    pub shared_clean: u64,
+    pub shared_dirty: u64,
+    pub private_clean: u64,
+    pub private_dirty: u64,
+    pub anonymous: u64,
+    pub swap: u64,
+    pub swap_pss: u64,
+}
+impl MemoryUsage {
+    /// Unique set size - memory that would be freed if the process exited
+    pub fn uss(&self) -> u64 {
+        self.private_clean + self.private_dirty
+    }
+
+    /// Returns file backed part of resident memory
+    pub fn file_backed(&self) -> u64 {
+        self.rss.saturating_sub(self.anonymous)
+    }
+
+    /// Sets counter from a smaps line like `Pss:  494 kB`, returns false for unknown keys
+    fn set(&mut self, key: &str, kb: u64) -> bool {
+        let field = match key {
+            "Size" => &mut self.size,
+            "Rss" => &mut self.rss,
+            "Pss" => &mut self.pss,
+            "Pss_Anon" => &mut self.pss_anon,
+            "Pss_File" => &mut self.pss_file,
+            "Pss_Shmem" => &mut self.pss_shmem,
+            "Shared_Clean" => &mut self.shared_clean,
+            "Shared_Dirty" => &mut self.shared_dirty,
+            "Private_Clean" => &mut self.private_clean,
+            "Private_Dirty" => &mut self.private_dirty,
+            "Anonymous" => &mut self.anonymous,
+            "Swap" => &mut self.swap,
+            "SwapPss" => &mut self.swap_pss,
+            _ => return false,
+        };
+        *field = kb * 1024;
+        true
+    }
+}
+impl std::ops::AddAssign for MemoryUsage {
+    fn add_assign(&mut self, other: MemoryUsage) {
+        self.size += other.size;
+        self.rss += other.rss;
+        self.pss += other.pss;
+        self.pss_anon += other.pss_anon;
+        self.pss_file += other.pss_file;
+        self.pss_shmem += other.pss_shmem;
+        self.shared_clean += other.shared_clean;
+        self.shared_dirty += other.shared_dirty;
+        self.private_clean += other.private_clean;
+        self.private_dirty += other.private_dirty;
+        self.anonymous += other.anonymous;
+        self.swap += other.swap;
+        self.swap_pss += other.swap_pss;
+    }
+}
+
+/// Single line of `/proc/[pid]/maps`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct MemoryMapping {
+    pub start: u64,
+    pub end: u64,
+    /// Permissions like `r-xp`
+    pub perms: String,
+    pub offset: u64,
+    /// Device as `major:minor`
+    pub device: String,
+    pub inode: u64,
+    /// Mapped file or pseudo path like `[heap]`, `None` for anonymous mappings
+    pub path: Option<String>,
+    /// Counters from smaps, `None` if read from maps
+    pub usage: Option<MemoryUsage>,
+}
+impl MemoryMapping {
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+pub struct MemoryDetail {
+    pub total: MemoryUsage,
+    pub mappings: Vec<MemoryMapping>,
+}
+impl MemoryDetail {
+    /// Sums mappings by path, anonymous ones as `[anon]`. Uses pss when smaps
+    /// were read and virtual size otherwise. Sorted from the largest.
+    pub fn by_path(&self) -> Vec<(String, u64)> {
+        let mut paths: Vec<(String, u64)> = vec![];
+        for m in &self.mappings {
+            let path = m.path.clone().unwrap_or_else(|| "[anon]".to_string());
+            let size = m.usage.map_or(m.size(), |u| u.pss);
+            match paths.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, total)) => *total += size,
+                None => paths.push((path, size)),
+            }
+        }
+        paths.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        paths
+    }
+}
+
+impl FromStr for ProcessState {
+    type Err = anyhow::Error;
+
+    /// Parses state letter like `R` as in `ps` output or state name like `running`
+    fn from_str(s: &str) -> Result<ProcessState> {
+        use self::ProcessState::*;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if ProcessState::from(c) != Unknown => return Ok(ProcessState::from(c)),
+            _ => {}
+        }
+        [Running, Sleeping, Waiting, Zombie, Stopped, TracingStop, Dead, Wakekill, Waking, Parked]
+            .iter()
+            .copied()
+            .find(|state| format!("{:?}", state).eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow!("unknown process state {}", s))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
 pub struct Process {
     /// The process ID.
     pub pid: u32,
@@ -54,20 +278,56 @@ pub struct Process {
     pub ppid: u32,
     /// The process group ID of the process.
     pub pgrp: u32,
+    /// The session ID of the process.
+    pub session: u32,
+    /// The controlling terminal of the process, 0 if none.
+    pub tty_nr: u32,
     /// Amount of time that this process has been scheduled
     /// in user mode, measured in clock ticks.
     pub utime: u64,
     /// Amount of time that this process has been scheduled
     /// in kernel mode, measured in clock ticks.
     pub stime: u64,
+    /// Kernel scheduling priority, negated real-time priority minus one for real-time processes.
+    pub priority: i64,
+    /// The nice value, from 19 (low priority) to -20 (high priority).
+    pub nice: i64,
+    pub num_threads: u64,
+    /// The time the process started after system boot, measured in clock ticks.
+    pub starttime: u64,
+    /// CPU number last executed on.
+    pub processor: u32,
+    /// Real-time scheduling priority, 0 for non real-time processes.
+    pub rt_priority: u32,
+    /// Scheduling policy, see `sched_policy()`.
+    pub policy: u32,
     /// total program size
     pub size: u64,
     /// resident set size
     pub resident: u64,
     /// number of resident shared pages (i.e., backed by a file)
     pub shared: u64,
+    /// Real user ID.
     pub uid: u32,
+    pub euid: u32,
+    pub suid: u32,
+    /// User ID used for filesystem access checks.
+    pub fsuid: u32,
+    /// Real group ID.
     pub gid: u32,
+    pub egid: u32,
+    pub sgid: u32,
+    pub fsgid: u32,
+    /// Supplementary group IDs.
+    pub groups: Vec<u32>,
+    /// Badness score from 0 to 1000, the process with the highest one is killed first when out of memory
+    pub oom_score: i32,
+    /// Adjustment of the oom score from -1000 (never kill) to 1000
+    pub oom_score_adj: i32,
+    /// Executable of the process, `None` for kernel threads or without permission
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub root: Option<PathBuf>,
 }
 impl Process {
     pub fn new(pid: u32) -> Result<Process> {
@@ -77,10 +337,15 @@ impl Process {
         proc.parse_proc_stat(&stats)?;
         let statsm = fs::read_to_string(p.join("statm"))?;
         proc.parse_proc_statm(&statsm)?;
-        let (uid, gid) = Self::uid_gid(pid)?;
-        proc.uid = uid;
-        proc.gid = gid;
+        let status = fs::read_to_string(p.join("status"))?;
+        proc.parse_proc_status(&status)?;
         proc.cmd = Self::cmd(pid)?;
+        let read_num = |name: &str| fs::read_to_string(p.join(name)).ok().and_then(|s| s.trim().parse::<i32>().ok());
+        proc.oom_score = read_num("oom_score").unwrap_or(0);
+        proc.oom_score_adj = read_num("oom_score_adj").unwrap_or(0);
+        proc.exe = fs::read_link(p.join("exe")).ok();
+        proc.cwd = fs::read_link(p.join("cwd")).ok();
+        proc.root = fs::read_link(p.join("root")).ok();
 
         Ok(proc)
     }
@@ -118,6 +383,57 @@ impl Process {
         self.stime as f64 / utils::clk_tick() as f64
     }
 
+    /// Returns average cpu usage in percent over the lifetime of process,
+    /// the same way `ps` calculates it
+    pub fn cpu_usage(&self, uptime: f64) -> f64 {
+        let elapsed = uptime - self.starttime as f64 / utils::clk_tick() as f64;
+        if elapsed <= 0. {
+            return 0.;
+        }
+        self.cpu_time() / elapsed * 100.
+    }
+
+    /// Returns seconds since unix epoch when the process started
+    pub fn start_time(&self, boot_time: u64) -> u64 {
+        boot_time + self.starttime / utils::clk_tick()
+    }
+
+    /// Returns name of the scheduling policy like `chrt` shows it
+    pub fn sched_policy(&self) -> &'static str {
+        match self.policy {
+            0 => "SCHED_OTHER",
+            1 => "SCHED_FIFO",
+            2 => "SCHED_RR",
+            3 => "SCHED_BATCH",
+            5 => "SCHED_IDLE",
+            6 => "SCHED_DEADLINE",
+            _ => "unknown",
+        }
+    }
+
+    /// Returns name of the controlling terminal like `pts/3`
+    pub fn tty(&self) -> Option<String> {
+        let major = (self.tty_nr >> 8) & 0xfff;
+        let minor = (self.tty_nr & 0xff) | ((self.tty_nr >> 12) & 0xfff00);
+        match major {
+            0 => None,
+            4 if minor < 64 => Some(format!("tty{}", minor)),
+            4 => Some(format!("ttyS{}", minor - 64)),
+            5 if minor == 0 => Some("tty".to_string()),
+            136..=143 => Some(format!("pts/{}", (major - 136) * 256 + minor)),
+            _ => Some(format!("{}:{}", major, minor)),
+        }
+    }
+
+    /// Returns environment variables of process. Readable only by its owner.
+    pub fn environ(&self) -> Result<Vec<(String, String)>> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        Ok(Self::_environ(&fs::read_to_string(p.join("environ"))?))
+    }
+    pub(crate) fn _environ(out: &str) -> Vec<(String, String)> {
+        out.split('\u{0}').filter_map(|var| var.split_once('=')).map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
     /// Updates all fields of current process
     pub fn update(&mut self) -> Result<()> {
         self.update_stat()?;
@@ -167,13 +483,43 @@ impl Process {
         if let Some(pgrp) = attrs.next() {
             self.pgrp = pgrp.parse::<u32>()?;
         }
-        let mut attrs2 = attrs.skip(8);
+        if let Some(session) = attrs.next() {
+            self.session = session.parse::<u32>()?;
+        }
+        if let Some(tty_nr) = attrs.next() {
+            self.tty_nr = tty_nr.parse::<u32>()?;
+        }
+        let mut attrs2 = attrs.skip(6);
         if let Some(utime) = attrs2.next() {
             self.utime = utime.parse::<u64>()?;
         }
         if let Some(stime) = attrs2.next() {
             self.stime = stime.parse::<u64>()?;
         }
+        let mut attrs3 = attrs2.skip(2);
+        if let Some(priority) = attrs3.next() {
+            self.priority = priority.parse::<i64>()?;
+        }
+        if let Some(nice) = attrs3.next() {
+            self.nice = nice.parse::<i64>()?;
+        }
+        if let Some(num_threads) = attrs3.next() {
+            self.num_threads = num_threads.parse::<u64>()?;
+        }
+        let mut attrs4 = attrs3.skip(1);
+        if let Some(starttime) = attrs4.next() {
+            self.starttime = starttime.parse::<u64>()?;
+        }
+        let mut attrs5 = attrs4.skip(16);
+        if let Some(processor) = attrs5.next() {
+            self.processor = processor.parse::<u32>()?;
+        }
+        if let Some(rt_priority) = attrs5.next() {
+            self.rt_priority = rt_priority.parse::<u32>()?;
+        }
+        if let Some(policy) = attrs5.next() {
+            self.policy = policy.trim_end().parse::<u32>()?;
+        }
 
         Ok(())
     }
@@ -195,21 +541,321 @@ impl Process {
         Ok(())
     }
 
-    /// Returns (uid, gid) of process
-    pub(crate) fn uid_gid(pid: u32) -> Result<(u32, u32)> {
-        let p = PathBuf::from(format!("/proc/{}", pid));
-        let status = fs::read_to_string(p.join("status"))?;
-        let mut uid = 0;
-        let mut gid = 0;
-        let uid_re = Regex::new(r"Uid:\s+(\d+)").unwrap();
-        let gid_re = Regex::new(r"Gid:\s+(\d+)").unwrap();
-        for m in uid_re.captures_iter(&status) {
-            uid = m[1].parse::<u32>()?;
+    /// Internal function to parse out user and group ids
+    /// of process from /proc/[pid]/status
+    pub(crate) fn parse_proc_status(&mut self, out: &str) -> Result<()> {
+        for line in out.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) if matches!(key, "Uid" | "Gid" | "Groups") => (key, value),
+                _ => continue,
+            };
+            let ids = value.split_whitespace().map(|id| id.parse::<u32>()).collect::<Result<Vec<u32>, _>>()?;
+            match (key, &ids[..]) {
+                ("Uid", [real, effective, saved, fs]) => {
+                    self.uid = *real;
+                    self.euid = *effective;
+                    self.suid = *saved;
+                    self.fsuid = *fs;
+                }
+                ("Gid", [real, effective, saved, fs]) => {
+                    self.gid = *real;
+                    self.egid = *effective;
+                    self.sgid = *saved;
+                    self.fsgid = *fs;
+                }
+                ("Groups", _) => self.groups = ids,
+                _ => {}
+            }
         }
-        for m in gid_re.captures_iter(&status) {
-            gid = m[1].parse::<u32>()?;
+
+        Ok(())
+    }
+
+    /// Returns name of the real user of process
+    pub fn user(&self) -> String {
+        UserCache::get().user(self.uid)
+    }
+
+    /// Returns name of the effective user of process
+    pub fn effective_user(&self) -> String {
+        UserCache::get().user(self.euid)
+    }
+
+    /// Returns name of the real group of process
+    pub fn group(&self) -> String {
+        UserCache::get().group(self.gid)
+    }
+
+    /// Returns names of supplementary groups of process
+    pub fn group_names(&self) -> Vec<String> {
+        let cache = UserCache::get();
+        self.groups.iter().map(|gid| cache.group(*gid)).collect()
+    }
+
+    /// Returns memory totals from smaps_rollup with mappings from maps. Reading smaps_rollup
+    /// is much cheaper than smaps, but on kernels older than 4.14 this falls back to it.
+    pub fn memory_detail(&self) -> Result<MemoryDetail> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        let total = match fs::read_to_string(p.join("smaps_rollup")) {
+            Ok(out) => Self::_smaps(&out).into_iter().filter_map(|m| m.usage).next().unwrap_or_default(),
+            Err(_) => return self.smaps(),
+        };
+        let mappings = Self::_smaps(&fs::read_to_string(p.join("maps"))?);
+        Ok(MemoryDetail { total, mappings })
+    }
+
+    /// Returns memory usage of every mapping from smaps
+    pub fn smaps(&self) -> Result<MemoryDetail> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        let mappings = Self::_smaps(&fs::read_to_string(p.join("smaps"))?);
+        let mut total = MemoryUsage::default();
+        for usage in mappings.iter().filter_map(|m| m.usage) {
+            total += usage;
+        }
+        Ok(MemoryDetail { total, mappings })
+    }
+
+    /// Parses maps, smaps and smaps_rollup which share the mapping header line
+    pub(crate) fn _smaps(out: &str) -> Vec<MemoryMapping> {
+        let mut mappings: Vec<MemoryMapping> = vec![];
+        for line in out.lines() {
+            let mut fields = line.split_whitespace();
+            let first = fields.next().unwrap_or("");
+            if let Some(key) = first.strip_suffix(':') {
+                let kb = fields.next().and_then(|kb| kb.parse::<u64>().ok()).unwrap_or(0);
+                if let Some(mapping) = mappings.last_mut() {
+                    let mut usage = mapping.usage.unwrap_or_default();
+                    if usage.set(key, kb) {
+                        mapping.usage = Some(usage);
+                    }
+                }
+                continue;
+            }
+            let (start, end) = match first.split_once('-') {
+                Some(range) => range,
+                None => continue,
+            };
+            let hex = |s: &str| u64::from_str_radix(s, 16).unwrap_or(0);
+            // path is separated from inode by padding and may contain spaces itself
+            let mut parts = line.splitn(6, ' ').skip(1);
+            mappings.push(MemoryMapping {
+                start: hex(start),
+                end: hex(end),
+                perms: parts.next().unwrap_or("").to_string(),
+                offset: hex(parts.next().unwrap_or("")),
+                device: parts.next().unwrap_or("").to_string(),
+                inode: parts.next().and_then(|i| i.parse::<u64>().ok()).unwrap_or(0),
+                path: parts.next().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string),
+                usage: None,
+            });
         }
-        Ok((uid, gid))
+        mappings
+    }
+
+    /// Returns true if the process still runs and wasn't replaced by another one with
+    /// the same pid, which is checked by comparing start times. Zombies count as exited.
+    pub fn is_running(&self) -> bool {
+        let mut current = Process::default();
+        fs::read_to_string(format!("/proc/{}/stat", self.pid)).is_ok_and(|stat| current.parse_proc_stat(&stat).is_ok())
+            && current.starttime == self.starttime
+            && current.state != ProcessState::Zombie
+            && current.state != ProcessState::Dead
+    }
+
+    /// Returns true for kthreadd and the kernel threads it spawns
+    pub fn is_kernel_thread(&self) -> bool {
+        self.pid == 2 || self.ppid == 2
+    }
+
+    // Guards against signalling an unrelated process which reused the pid
+    fn check_running(&self) -> Result<()> {
+        if !self.is_running() {
+            return Err(anyhow!("process {} ({}) is no longer running", self.pid, self.name));
+        }
+        Ok(())
+    }
+
+    /// Sends signal to the process
+    pub fn signal(&self, signal: Signal) -> Result<()> {
+        self.check_running()?;
+        if unsafe { libc::kill(self.pid as libc::pid_t, signal.number()) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Sends SIGKILL to the process
+    pub fn kill(&self) -> Result<()> {
+        self.signal(Signal::Kill)
+    }
+
+    /// Sends SIGTERM and waits up to `timeout` for the process to exit, then sends SIGKILL.
+    /// Returns true if the process exited before the timeout.
+    pub fn terminate(&self, timeout: Duration) -> Result<bool> {
+        self.signal(Signal::Term)?;
+        if self.wait(timeout) {
+            return Ok(true);
+        }
+        match self.kill() {
+            Ok(()) => Ok(false),
+            // exited right after the timeout
+            Err(_) if !self.is_running() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Waits up to `timeout` for the process to exit, returns true if it did
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let start = std::time::Instant::now();
+        while self.is_running() {
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    /// Returns thread ids of the process
+    pub fn tasks(&self) -> Result<Vec<u32>> {
+        let mut tasks = vec![];
+        for entry in fs::read_dir(format!("/proc/{}/task", self.pid))? {
+            if let Ok(tid) = entry?.file_name().to_string_lossy().parse::<u32>() {
+                tasks.push(tid);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Sets nice value of all threads of the process. Lowering it requires CAP_SYS_NICE.
+    pub fn renice(&self, nice: i32) -> Result<()> {
+        self.check_running()?;
+        for tid in self.tasks()? {
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, tid as libc::id_t, nice) } != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns cpus the process is allowed to run on
+    pub fn affinity(&self) -> Result<Vec<u32>> {
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        if unsafe { libc::sched_getaffinity(self.pid as libc::pid_t, size, &mut set) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| unsafe { libc::CPU_ISSET(*cpu, &set) })
+            .map(|c| c as u32)
+            .collect())
+    }
+
+    /// Restricts all threads of the process to run on `cpus` only
+    pub fn set_affinity(&self, cpus: &[u32]) -> Result<()> {
+        self.check_running()?;
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        for cpu in cpus {
+            if *cpu as usize >= libc::CPU_SETSIZE as usize {
+                return Err(anyhow!("cpu {} is out of range", cpu));
+            }
+            unsafe { libc::CPU_SET(*cpu as usize, &mut set) };
+        }
+        let size = std::mem::size_of::<libc::cpu_set_t>();
+        for tid in self.tasks()? {
+            if unsafe { libc::sched_setaffinity(tid as libc::pid_t, size, &set) } != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns resource limits of the process
+    pub fn limits(&self) -> Result<Limits> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        Ok(Self::_limits(&fs::read_to_string(p.join("limits"))?))
+    }
+    pub(crate) fn _limits(out: &str) -> Limits {
+        let mut lines = out.lines();
+        // columns are aligned to the header, names contain spaces so split at its offsets
+        let header = lines.next().unwrap_or("");
+        let soft_at = header.find("Soft Limit").unwrap_or(26);
+        let value = |v: Option<&str>| v.and_then(|v| v.parse::<u64>().ok());
+        Limits(
+            lines
+                .filter(|l| l.len() > soft_at)
+                .map(|line| {
+                    let mut values = line[soft_at..].split_whitespace();
+                    Limit {
+                        name: line[..soft_at].trim().to_string(),
+                        soft: value(values.next()),
+                        hard: value(values.next()),
+                        unit: values.next().map(str::to_string),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns number of open file descriptors, compare with `Max open files` limit
+    pub fn open_files(&self) -> Result<usize> {
+        Ok(fs::read_dir(format!("/proc/{}/fd", self.pid))?.count())
+    }
+
+    /// Returns memory of the process split by numa node it is allocated on
+    pub fn numa_placement(&self) -> Result<NumaPlacement> {
+        let out = fs::read_to_string(format!("/proc/{}/numa_maps", self.pid))?;
+        Ok(NumaPlacement { pid: self.pid, name: self.name.clone(), nodes: Self::_numa_maps(&out) })
+    }
+    pub(crate) fn _numa_maps(out: &str) -> std::collections::BTreeMap<u32, u64> {
+        let mut nodes = std::collections::BTreeMap::new();
+        for line in out.lines() {
+            let mut pages = vec![];
+            let mut page_size = 4;
+            for field in line.split_whitespace().skip(2) {
+                match field.split_once('=') {
+                    Some(("kernelpagesize_kB", kb)) => page_size = kb.parse::<u64>().unwrap_or(page_size),
+                    Some((node, n)) if node.starts_with('N') => {
+                        if let (Ok(node), Ok(n)) = (node[1..].parse::<u32>(), n.parse::<u64>()) {
+                            pages.push((node, n));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // page size comes last on the line so sum up after reading it
+            for (node, n) in pages {
+                *nodes.entry(node).or_insert(0) += n * page_size * 1024;
+            }
+        }
+        nodes
+    }
+
+    /// Returns namespaces of the process. Reading them requires ptrace access to the process,
+    /// without it an error is returned rather than namespaces reported as unsupported.
+    pub fn namespaces(&self) -> Result<Namespaces> {
+        let ns = PathBuf::from(format!("/proc/{}/ns", self.pid));
+        // mnt is always present so a failure means the process is gone or not permitted
+        fs::read_link(ns.join("mnt"))?;
+        procfs::_namespaces(&ns)
+    }
+
+    /// Returns capability sets and seccomp state of the process
+    pub fn capabilities(&self) -> Result<Capabilities> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        Ok(procfs::_capabilities(&fs::read_to_string(p.join("status"))?))
+    }
+
+    /// Returns cgroups the process belongs to
+    pub fn cgroups(&self) -> Result<Cgroups> {
+        let p = PathBuf::from(format!("/proc/{}", self.pid));
+        Ok(procfs::_proc_cgroups(&fs::read_to_string(p.join("cgroup"))?))
+    }
+
+    /// Returns resource usage and limits of the cgroup of process
+    pub fn cgroup_stats(&self) -> Result<CgroupStats> {
+        // paths are resolved against rustop's own mounts, which matter inside containers
+        Ok(procfs::_cgroup_stats(SysProperty::Cgroup.path(), &self.cgroups()?.relative_to(&procfs::cgroup_mounts())))
     }
 
     /// Returns a full command line of process
@@ -221,21 +867,174 @@ impl Process {
         }
     }
     pub(crate) fn _cmd(out: &str) -> String {
-        out.trim_end_matches('\u{0}').replace('\u{0}', &" ")
+        out.trim_end_matches('\u{0}').replace('\u{0}', " ")
     }
 }
 
-type Processes = Vec<Process>;
+pub type Processes = Vec<Process>;
 pub fn tree() -> Result<Processes> {
     let mut ps = Vec::new();
     for entry in fs::read_dir("/proc")? {
-        let e = entry.unwrap();
+        let e = entry?;
         if let Some(name) = e.path().file_name() {
             let _name = name.to_string_lossy();
             if utils::is_numeric(&_name) {
-                ps.push(Process::new(_name.parse::<u32>()?)?);
+                // the process might have exited since listing /proc
+                if let Ok(process) = Process::new(_name.parse::<u32>()?) {
+                    ps.push(process);
+                }
             }
         }
     }
     Ok(ps)
 }
+
+/// Selects processes matching all of the set criteria, like `pgrep` does.
+///
+/// ```no_run
+/// # use rustop::ps::ProcessFilter;
+/// let nginx = ProcessFilter::new().name("^nginx")?.user("www-data").min_rss(50 << 20).processes()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct ProcessFilter {
+    name: Option<Regex>,
+    cmd: Option<Regex>,
+    user: Option<String>,
+    state: Option<ProcessState>,
+    ppid: Option<u32>,
+    min_rss: Option<u64>,
+    min_cpu: Option<f64>,
+    cgroup: Option<String>,
+    privileged: bool,
+}
+impl ProcessFilter {
+    pub fn new() -> ProcessFilter {
+        ProcessFilter::default()
+    }
+
+    /// Matches process name against regex `pattern`
+    pub fn name(mut self, pattern: &str) -> Result<ProcessFilter> {
+        self.name = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Matches full command line against regex `pattern`
+    pub fn cmd(mut self, pattern: &str) -> Result<ProcessFilter> {
+        self.cmd = Some(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Matches real user by name or uid
+    pub fn user(mut self, user: &str) -> ProcessFilter {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn state(mut self, state: ProcessState) -> ProcessFilter {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn ppid(mut self, ppid: u32) -> ProcessFilter {
+        self.ppid = Some(ppid);
+        self
+    }
+
+    /// Minimum resident set size in bytes
+    pub fn min_rss(mut self, bytes: u64) -> ProcessFilter {
+        self.min_rss = Some(bytes);
+        self
+    }
+
+    /// Minimum cpu usage in percent, see `Process::cpu_usage`
+    pub fn min_cpu(mut self, percent: f64) -> ProcessFilter {
+        self.min_cpu = Some(percent);
+        self
+    }
+
+    /// Matches processes in cgroup `path` or any of its descendants
+    pub fn cgroup(mut self, path: &str) -> ProcessFilter {
+        self.cgroup = Some(path.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Matches processes with any effective, permitted or ambient capabilities
+    pub fn privileged(mut self) -> ProcessFilter {
+        self.privileged = true;
+        self
+    }
+
+    /// Returns true if process matches all criteria. Cpu usage is relative to `uptime`.
+    pub fn matches(&self, p: &Process, uptime: f64) -> bool {
+        self.name.as_ref().is_none_or(|re| re.is_match(&p.name))
+            && self.cmd.as_ref().is_none_or(|re| re.is_match(&p.cmd))
+            && self.user.as_ref().is_none_or(|user| *user == p.uid.to_string() || *user == p.user())
+            && self.state.is_none_or(|state| state == p.state)
+            && self.ppid.is_none_or(|ppid| ppid == p.ppid)
+            && self.min_rss.is_none_or(|rss| p.resident() >= rss)
+            && self.min_cpu.is_none_or(|cpu| p.cpu_usage(uptime) >= cpu)
+            && self.cgroup.as_ref().is_none_or(|cgroup| {
+                // root cgroup `/` is trimmed to an empty string and matches everything
+                p.cgroups().is_ok_and(|c| {
+                    let path = c.path();
+                    cgroup.is_empty() || path == cgroup || path.starts_with(&format!("{}/", cgroup))
+                })
+            })
+            && (!self.privileged || p.capabilities().is_ok_and(|caps| caps.is_privileged()))
+    }
+
+    /// Returns running processes matching all criteria
+    pub fn processes(&self) -> Result<Processes> {
+        let uptime = procfs::_uptime(&fs::read_to_string(SysProperty::Uptime.path())?);
+        Ok(tree()?.into_iter().filter(|p| self.matches(p, uptime)).collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessSort {
+    Pid,
+    Name,
+    User,
+    /// Largest resident set size first
+    Rss,
+    /// Highest cpu usage first
+    Cpu,
+    /// Most cpu time first
+    Time,
+    /// Oldest first
+    Start,
+    /// Most likely out of memory victim first
+    Oom,
+}
+impl FromStr for ProcessSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<ProcessSort> {
+        match s {
+            "pid" => Ok(ProcessSort::Pid),
+            "name" => Ok(ProcessSort::Name),
+            "user" => Ok(ProcessSort::User),
+            "rss" | "mem" => Ok(ProcessSort::Rss),
+            "cpu" => Ok(ProcessSort::Cpu),
+            "time" => Ok(ProcessSort::Time),
+            "start" => Ok(ProcessSort::Start),
+            "oom" => Ok(ProcessSort::Oom),
+            _ => Err(anyhow!("unknown sort key {}", s)),
+        }
+    }
+}
+impl ProcessSort {
+    pub fn sort(self, ps: &mut [Process], uptime: f64) {
+        match self {
+            ProcessSort::Pid => ps.sort_by_key(|p| p.pid),
+            ProcessSort::Name => ps.sort_by(|a, b| a.name.cmp(&b.name)),
+            ProcessSort::User => ps.sort_by_cached_key(|p| p.user()),
+            ProcessSort::Rss => ps.sort_by_key(|p| std::cmp::Reverse(p.resident)),
+            ProcessSort::Cpu => ps.sort_by(|a, b| b.cpu_usage(uptime).total_cmp(&a.cpu_usage(uptime))),
+            ProcessSort::Time => ps.sort_by_key(|p| std::cmp::Reverse(p.utime + p.stime)),
+            ProcessSort::Start => ps.sort_by_key(|p| p.starttime),
+            ProcessSort::Oom => ps.sort_by_key(|p| (std::cmp::Reverse(p.oom_score), p.pid)),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use super::*;
+
+/// Capability names indexed by their bit number
+pub static CAPABILITIES: [&str; 41] = [
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+/// Set of capabilities as the bitmask shown in `/proc/[pid]/status`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct CapabilitySet(pub u64);
+impl CapabilitySet {
+    /// Returns names of capabilities in the set, unknown ones as `CAP_<bit>`
+    pub fn names(&self) -> Vec<String> {
+        (0..64)
+            .filter(|bit| self.0 & (1 << bit) != 0)
+            .map(|bit| CAPABILITIES.get(bit).map_or(format!("CAP_{}", bit), |name| name.to_string()))
+            .collect()
+    }
+
+    /// Checks capability by name like `CAP_SYS_ADMIN` or `sys_admin`
+    pub fn has(&self, name: &str) -> bool {
+        let name = name.to_uppercase();
+        let name = if name.starts_with("CAP_") { name } else { format!("CAP_{}", name) };
+        CAPABILITIES.iter().position(|cap| *cap == name).is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Seccomp {
+    #[default]
+    Disabled,
+    /// Only read, write, exit and sigreturn are allowed
+    Strict,
+    Filter,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Capabilities {
+    pub inheritable: CapabilitySet,
+    pub permitted: CapabilitySet,
+    pub effective: CapabilitySet,
+    pub bounding: CapabilitySet,
+    pub ambient: CapabilitySet,
+    /// Whether execve can't grant privileges, set with `PR_SET_NO_NEW_PRIVS`
+    pub no_new_privs: bool,
+    pub seccomp: Seccomp,
+    /// Number of attached seccomp filters, `None` before Linux 5.9
+    pub seccomp_filters: Option<u32>,
+}
+impl Capabilities {
+    /// Returns true if the process can use any capability right now or gain them in `execve`
+    pub fn is_privileged(&self) -> bool {
+        !self.effective.is_empty() || !self.permitted.is_empty() || !self.ambient.is_empty()
+    }
+}
+
+/// Inode numbers identifying namespaces of a process. Processes in the same namespace
+/// have the same inode, `None` means the kernel doesn't support the namespace type.
+/// Namespaces that can't be read for lack of permissions are an error, never `None`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Namespaces {
+    pub cgroup: Option<u64>,
+    pub ipc: Option<u64>,
+    pub mnt: Option<u64>,
+    pub net: Option<u64>,
+    pub pid: Option<u64>,
+    pub time: Option<u64>,
+    pub user: Option<u64>,
+    pub uts: Option<u64>,
+}
+impl Namespaces {
+    fn all(&self) -> [(&'static str, Option<u64>); 8] {
+        [
+            ("cgroup", self.cgroup),
+            ("ipc", self.ipc),
+            ("mnt", self.mnt),
+            ("net", self.net),
+            ("pid", self.pid),
+            ("time", self.time),
+            ("user", self.user),
+            ("uts", self.uts),
+        ]
+    }
+
+    /// Returns types of namespaces which differ from `other`, like a container's from init's
+    pub fn diff(&self, other: &Namespaces) -> Vec<&'static str> {
+        self.all().iter().zip(other.all().iter()).filter(|(a, b)| a.1 != b.1).map(|(a, _)| a.0).collect()
+    }
+}
@@ -1,28 +1,185 @@
 use super::*;
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SensorKind {
+    #[default]
+    Temperature,
+    Voltage,
+    Fan,
+    Current,
+    Power,
+    Energy,
+}
+impl SensorKind {
+    pub fn all() -> [SensorKind; 6] {
+        use self::SensorKind::*;
+        [Temperature, Voltage, Fan, Current, Power, Energy]
+    }
+
+    /// Prefix of hwmon sysfs attributes of this kind
+    pub fn prefix(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temp",
+            SensorKind::Voltage => "in",
+            SensorKind::Fan => "fan",
+            SensorKind::Current => "curr",
+            SensorKind::Power => "power",
+            SensorKind::Energy => "energy",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Voltage => "V",
+            SensorKind::Fan => "RPM",
+            SensorKind::Current => "A",
+            SensorKind::Power => "W",
+            SensorKind::Energy => "J",
+        }
+    }
+
+    /// Divisor converting raw hwmon values (m°C, mV, mA, µW, µJ) to base units
+    pub fn scale(self) -> f32 {
+        match self {
+            SensorKind::Fan => 1.,
+            SensorKind::Power | SensorKind::Energy => 1_000_000.,
+            _ => 1000.,
+        }
+    }
+
+    pub(crate) fn from_prefix(prefix: &str) -> Option<SensorKind> {
+        SensorKind::all().iter().copied().find(|kind| kind.prefix() == prefix)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct Sensor {
+    /// Sensor label or attribute name like `fan1` if unlabeled
     pub name: String,
-    pub temp: f32,
+    /// Defaults to temperature for output of versions reading only temperatures
+    #[serde(default)]
+    pub kind: SensorKind,
+    /// Current reading in base units of the sensor kind
+    #[serde(alias = "temp")]
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub crit: Option<f32>,
+    pub alarm: Option<bool>,
+    pub min_alarm: Option<bool>,
+    pub max_alarm: Option<bool>,
+    pub crit_alarm: Option<bool>,
+    /// Set if the sensor is present but its value couldn't be read
+    pub error: Option<String>,
+}
+impl Sensor {
+    /// Returns true if any of the alarm flags is raised
+    pub fn in_alarm(&self) -> bool {
+        [self.alarm, self.min_alarm, self.max_alarm, self.crit_alarm].contains(&Some(true))
+    }
+
+    /// Returns how close the reading is to the critical limit (or the high
+    /// limit if there is no critical one), where 1.0 means it was reached
+    pub fn crit_ratio(&self) -> Option<f32> {
+        self.crit.or(self.max).filter(|limit| *limit > 0.).map(|limit| self.value / limit)
+    }
 }
 
 type Sensors = Vec<Sensor>;
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct DeviceSensors {
     pub name: String,
     pub sensors: Sensors,
+    /// Set if the device directory couldn't be listed
+    pub error: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
-pub struct Temperatures {
-    pub temp_devices: Vec<DeviceSensors>,
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Hwmon {
+    #[serde(alias = "temp_devices")]
+    pub devices: Vec<DeviceSensors>,
+}
+
+#[deprecated(note = "renamed to `Hwmon`, which also holds fans, voltages and power")]
+pub type Temperatures = Hwmon;
+impl Hwmon {
+    /// Returns only sensors of given kind, skipping devices without any unless they failed to read
+    pub fn only(self, kind: SensorKind) -> Hwmon {
+        let devices = self
+            .devices
+            .into_iter()
+            .map(|dev| DeviceSensors {
+                name: dev.name,
+                sensors: dev.sensors.into_iter().filter(|s| s.kind == kind).collect(),
+                error: dev.error,
+            })
+            .filter(|dev| !dev.sensors.is_empty() || dev.error.is_some())
+            .collect();
+        Hwmon { devices }
+    }
 }
 
-impl IntoIterator for Temperatures {
+impl IntoIterator for Hwmon {
     type Item = DeviceSensors;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.temp_devices.into_iter()
+        self.devices.into_iter()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct TripPoint {
+    /// active, passive, hot or critical
+    pub kind: String,
+    pub temp: f32,
+    pub hyst: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct CoolingDevice {
+    /// Name of the cooling device directory like `cooling_device0`
+    pub name: String,
+    pub kind: String,
+    pub cur_state: u32,
+    pub max_state: u32,
+    /// Trip point of the zone that activates this device
+    pub trip_point: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ThermalZone {
+    /// Name of the zone directory like `thermal_zone0`
+    pub name: String,
+    pub kind: String,
+    pub temp: f32,
+    pub mode: Option<String>,
+    pub policy: Option<String>,
+    pub trip_points: Vec<TripPoint>,
+    pub cooling_devices: Vec<CoolingDevice>,
+}
+impl ThermalZone {
+    /// Returns how close the zone is to its critical trip point
+    pub fn crit_ratio(&self) -> Option<f32> {
+        self.trip_points
+            .iter()
+            .find(|trip| trip.kind == "critical")
+            .filter(|trip| trip.temp > 0.)
+            .map(|trip| self.temp / trip.temp)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ThermalZones {
+    pub zones: Vec<ThermalZone>,
+}
+
+impl IntoIterator for ThermalZones {
+    type Item = ThermalZone;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.zones.into_iter()
     }
 }
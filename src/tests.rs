@@ -1,60 +1,688 @@
-#![allow(dead_code)]
 use super::*;
 
-#[cfg(test)]
 mod gets {
     use self::ps::*;
     use super::*;
+    use std::ops::Deref;
+    use std::path::PathBuf;
+
+    // Temporary directory removed when dropped, also when the test fails
+    struct FakeSysfs(PathBuf);
+    impl Deref for FakeSysfs {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+    impl AsRef<Path> for FakeSysfs {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+    impl Drop for FakeSysfs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // Creates a directory tree mimicking sysfs with given (path, content) files
+    fn fake_sysfs(name: &str, files: &[(&str, &str)]) -> FakeSysfs {
+        let root = std::env::temp_dir().join(format!("rustop-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        FakeSysfs(root)
+    }
     #[test]
     fn cpu_info() {
-        assert_eq!(procfs::_cpu_info(&tests::CPU_INFO), "AMD Ryzen 5 3600 6-Core Processor".to_string())
+        assert_eq!(procfs::_cpu_info(tests::CPU_INFO), "AMD Ryzen 5 3600 6-Core Processor".to_string())
     }
     #[test]
     fn total_clock_speed() {
-        assert_eq!(procfs::_total_clock_speed(&tests::CPU_INFO), 26040.395)
+        assert_eq!(procfs::_total_clock_speed(tests::CPU_INFO), 26040.395)
     }
     #[test]
     fn total_cpu_cores() {
-        assert_eq!(procfs::_total_cpu_cores(&tests::CPU_INFO), 12)
+        assert_eq!(procfs::_total_cpu_cores(tests::CPU_INFO), 12)
+    }
+    #[test]
+    fn cpu_topology() {
+        let topology = procfs::_cpu_topology(tests::CPU_INFO);
+        assert_eq!((topology.sockets, topology.cores, topology.threads), (1, 6, 12));
+        let p = &topology.processors[3];
+        assert_eq!(p.id, 3);
+        assert_eq!(p.vendor, "AuthenticAMD");
+        assert_eq!((p.family, p.model, p.stepping), (Some(23), Some(113), Some(0)));
+        assert_eq!(p.microcode, Some("0x8701013".to_string()));
+        assert_eq!(p.cache_size, Some(524288));
+        assert_eq!((p.physical_id, p.core_id), (0, 4));
+        assert_eq!(p.mhz, Some(2336.654));
+        assert!(topology.has_flag("avx2"));
+        assert!(!topology.has_flag("avx512f"));
+    }
+    #[test]
+    fn cpu_topology_arm() {
+        let topology = procfs::_cpu_topology(tests::CPU_INFO_ARM);
+        assert_eq!((topology.sockets, topology.cores, topology.threads), (1, 4, 4));
+        let p = &topology.processors[1];
+        assert_eq!(p.vendor, "0x41");
+        assert_eq!((p.model, p.stepping, p.mhz), (Some(0xd08), Some(3), None));
+        assert!(topology.has_flag("asimd"));
+        assert_eq!(procfs::_total_cpu_cores(tests::CPU_INFO_ARM), 4);
+    }
+    #[test]
+    fn cpu_freq() {
+        let root = fake_sysfs(
+            "cpufreq",
+            &[
+                ("cpu1/cpufreq/scaling_cur_freq", "3600000\n"),
+                ("cpu1/cpufreq/scaling_governor", "powersave\n"),
+                ("cpu0/cpufreq/scaling_cur_freq", "2200000\n"),
+                ("cpu0/cpufreq/scaling_min_freq", "800000\n"),
+                ("cpu0/cpufreq/scaling_max_freq", "4200000\n"),
+                ("cpu0/cpufreq/scaling_driver", "intel_pstate\n"),
+                ("cpu0/cpufreq/scaling_governor", "performance\n"),
+                ("cpu0/cpufreq/scaling_available_governors", "performance powersave\n"),
+                ("cpu0/cpufreq/energy_performance_preference", "balance_performance\n"),
+                ("cpu2/topology/core_id", "2\n"),
+                ("intel_pstate/no_turbo", "0\n"),
+            ],
+        );
+        let mut freq = procfs::_cpu_freq(&root).unwrap();
+        assert_eq!(freq.cores.len(), 2);
+        assert_eq!(
+            freq.cores[0],
+            CoreFreq {
+                cpu: 0,
+                cur: Some(2200000),
+                min: Some(800000),
+                max: Some(4200000),
+                hw_min: None,
+                hw_max: None,
+                driver: Some("intel_pstate".to_string()),
+                governor: Some("performance".to_string()),
+                available_governors: vec!["performance".to_string(), "powersave".to_string()],
+                energy_performance_preference: Some("balance_performance".to_string()),
+            }
+        );
+        assert_eq!(freq.boost, Some(true));
+        assert_eq!(freq.avg_mhz(), Some(2900.));
+        assert_eq!(freq.governors(), vec!["performance", "powersave"]);
+        assert!(!freq.governor_is("performance"));
+        freq.cores[1].governor = Some("performance".to_string());
+        assert!(freq.governor_is("performance"));
+    }
+    #[test]
+    fn hwmon() {
+        let root = fake_sysfs(
+            "hwmon",
+            &[
+                ("hwmon0/name", "nct6779\n"),
+                ("hwmon0/in0_input", "928\n"),
+                ("hwmon0/in0_label", "Vcore\n"),
+                ("hwmon0/in0_max", "1744\n"),
+                ("hwmon0/in0_alarm", "0\n"),
+                ("hwmon0/fan2_input", "1241\n"),
+                ("hwmon0/fan2_min", "300\n"),
+                ("hwmon0/fan2_alarm", "1\n"),
+                ("hwmon0/temp1_input", "47125\n"),
+                ("hwmon0/temp1_crit", "90000\n"),
+                ("hwmon0/temp1_max", "70000\n"),
+                ("hwmon0/temp1_min", "10000\n"),
+                ("hwmon0/temp1_crit_alarm", "0\n"),
+                ("hwmon1/name", "fam15h_power\n"),
+                ("hwmon1/power1_average", "23180000\n"),
+                ("hwmon1/power1_crit", "125000000\n"),
+            ],
+        );
+        let hwmon = procfs::_hwmon(&root).unwrap();
+        assert_eq!(hwmon.devices.len(), 2);
+        assert_eq!(
+            hwmon.devices[0].sensors,
+            vec![Sensor {
+                name: "power1".to_string(),
+                kind: SensorKind::Power,
+                value: 23.18,
+                min: None,
+                max: None,
+                crit: Some(125.),
+                alarm: None,
+                min_alarm: None,
+                max_alarm: None,
+                crit_alarm: None,
+                error: None,
+            }]
+        );
+        let nct = &hwmon.devices[1];
+        assert_eq!(nct.name, "nct6779");
+        assert_eq!(nct.sensors.iter().map(|s| s.kind).collect::<Vec<_>>(), SensorKind::all()[..3].to_vec());
+        let temp = &nct.sensors[0];
+        assert_eq!((temp.value, temp.min, temp.max, temp.crit), (47.125, Some(10.), Some(70.), Some(90.)));
+        assert_eq!((temp.crit_alarm, temp.in_alarm(), temp.crit_ratio()), (Some(false), false, Some(47.125 / 90.)));
+        assert!(nct.sensors[2].in_alarm());
+        assert_eq!((&nct.sensors[1].name[..], nct.sensors[1].value, nct.sensors[1].max), ("Vcore", 0.928, Some(1.744)));
+        assert_eq!(
+            (&nct.sensors[2].name[..], nct.sensors[2].min, nct.sensors[2].alarm),
+            ("fan2", Some(300.), Some(true))
+        );
+        assert_eq!(hwmon.only(SensorKind::Fan).devices.len(), 1);
+        // a device that couldn't be listed is kept so its error stays visible
+        let broken = DeviceSensors { name: "acpitz".into(), sensors: vec![], error: Some("denied".into()) };
+        assert_eq!(Hwmon { devices: vec![broken] }.only(SensorKind::Temperature).devices.len(), 1);
+
+        // the old temperature reader and serialized output keep working
+        #[allow(deprecated)]
+        let temp = async_std::task::block_on(procfs::sensor(root.join("hwmon0"), 1)).unwrap();
+        assert_eq!((temp.kind, temp.value), (SensorKind::Temperature, 47.125));
+        #[allow(deprecated)]
+        let missing = async_std::task::block_on(procfs::sensor(root.join("hwmon0"), 3));
+        assert!(missing.is_err());
+        let old: Hwmon =
+            serde_json::from_str(r#"{"temp_devices":[{"name":"k10temp","sensors":[{"name":"Tctl","temp":47.1}]}]}"#)
+                .unwrap();
+        assert_eq!((old.devices[0].sensors[0].kind, old.devices[0].sensors[0].value), (SensorKind::Temperature, 47.1));
+    }
+    #[test]
+    fn hwmon_sparse_unnamed() {
+        let root = fake_sysfs(
+            "hwmon-sparse",
+            &[
+                ("hwmon0/device/name", "nvme\n"),
+                ("hwmon0/temp2_input", "38850\n"),
+                ("hwmon0/temp5_input", "41850\n"),
+                ("hwmon0/temp5_label", "Sensor 2\n"),
+                ("hwmon0/temp7_input", "N/A\n"),
+                ("hwmon1/temp1_input", "30000\n"),
+            ],
+        );
+        // an input that exists but can't be read as a file
+        fs::create_dir(root.join("hwmon1/fan1_input")).unwrap();
+        let hwmon = procfs::_hwmon(&root).unwrap();
+        assert_eq!(hwmon.devices.iter().map(|d| &d.name[..]).collect::<Vec<_>>(), vec!["hwmon1", "nvme"]);
+        let nvme = &hwmon.devices[1].sensors;
+        assert_eq!(nvme.iter().map(|s| &s.name[..]).collect::<Vec<_>>(), vec!["temp2", "Sensor 2", "temp7"]);
+        assert_eq!((nvme[0].value, nvme[1].value), (38.85, 41.85));
+        assert!(nvme[0].error.is_none() && nvme[2].error.is_some());
+        let other = &hwmon.devices[0].sensors;
+        assert_eq!((other[0].value, other[0].error.is_none()), (30., true));
+        assert_eq!((other[1].kind, other[1].error.is_some()), (SensorKind::Fan, true));
+    }
+    #[test]
+    fn missing_sysfs_roots() {
+        let root = fake_sysfs("missing", &[("empty", "")]);
+        assert_eq!(procfs::_hwmon(&root.join("hwmon")).unwrap(), Hwmon::default());
+        assert_eq!(procfs::_usb_devices(&root.join("usb")).unwrap(), UsbDevices::default());
+        assert_eq!(procfs::_thermal_zones(&root.join("thermal")).unwrap(), ThermalZones::default());
+        assert_eq!(procfs::_power_supplies(&root.join("power_supply")).unwrap(), PowerSupplies::default());
+        assert_eq!(procfs::_numa_nodes(&root.join("node")).unwrap(), NumaNodes::default());
+        // a root that exists but isn't a directory is still an error
+        assert!(procfs::_hwmon(&root.join("empty")).is_err());
+    }
+    #[test]
+    fn thermal_zones() {
+        let root = fake_sysfs(
+            "thermal",
+            &[
+                ("thermal_zone1/type", "x86_pkg_temp\n"),
+                ("thermal_zone1/temp", "52000\n"),
+                ("thermal_zone0/type", "acpitz\n"),
+                ("thermal_zone0/temp", "27800\n"),
+                ("thermal_zone0/mode", "enabled\n"),
+                ("thermal_zone0/policy", "step_wise\n"),
+                ("thermal_zone0/trip_point_0_type", "critical\n"),
+                ("thermal_zone0/trip_point_0_temp", "119000\n"),
+                ("thermal_zone0/trip_point_1_type", "passive\n"),
+                ("thermal_zone0/trip_point_1_temp", "95000\n"),
+                ("thermal_zone0/trip_point_1_hyst", "2000\n"),
+                ("thermal_zone0/cdev0/type", "Processor\n"),
+                ("thermal_zone0/cdev0/cur_state", "1\n"),
+                ("thermal_zone0/cdev0/max_state", "3\n"),
+                ("thermal_zone0/cdev0_trip_point", "1\n"),
+                ("cooling_device0/type", "Processor\n"),
+            ],
+        );
+        let zones = procfs::_thermal_zones(&root).unwrap();
+        assert_eq!(zones.zones.len(), 2);
+        let zone = &zones.zones[0];
+        assert_eq!((&zone.name[..], &zone.kind[..], zone.temp), ("thermal_zone0", "acpitz", 27.8));
+        assert_eq!((zone.mode.as_deref(), zone.policy.as_deref()), (Some("enabled"), Some("step_wise")));
+        assert_eq!(zone.trip_points[1], TripPoint { kind: "passive".to_string(), temp: 95., hyst: Some(2.) });
+        assert_eq!(zone.crit_ratio(), Some(27.8 / 119.));
+        assert_eq!(
+            zone.cooling_devices,
+            vec![CoolingDevice {
+                name: "cdev0".to_string(),
+                kind: "Processor".to_string(),
+                cur_state: 1,
+                max_state: 3,
+                trip_point: Some(1),
+            }]
+        );
+        assert_eq!(zones.zones[1].crit_ratio(), None);
+    }
+    #[test]
+    fn power_supplies() {
+        let root = fake_sysfs(
+            "power",
+            &[
+                ("AC/type", "Mains\n"),
+                ("AC/online", "0\n"),
+                ("BAT0/type", "Battery\n"),
+                ("BAT0/status", "Discharging\n"),
+                ("BAT0/capacity", "87\n"),
+                ("BAT0/energy_now", "40000000\n"),
+                ("BAT0/energy_full", "45500000\n"),
+                ("BAT0/energy_full_design", "50000000\n"),
+                ("BAT0/power_now", "10000000\n"),
+                ("BAT0/cycle_count", "213\n"),
+                ("BAT1/type", "Battery\n"),
+                ("BAT1/status", "Charging\n"),
+                ("BAT1/charge_now", "1000000\n"),
+                ("BAT1/charge_full", "3000000\n"),
+                ("BAT1/charge_full_design", "3000000\n"),
+                ("BAT1/current_now", "500000\n"),
+                ("hidpp_battery_0/type", "Battery\n"),
+                ("hidpp_battery_0/scope", "Device\n"),
+            ],
+        );
+        let power = procfs::_power_supplies(&root).unwrap();
+        assert_eq!(power.adapters, vec![AcAdapter { name: "AC".to_string(), online: false }]);
+        assert!(!power.on_ac());
+        assert_eq!(power.batteries.len(), 2);
+        let bat0 = &power.batteries[0];
+        assert_eq!((bat0.capacity, bat0.cycle_count, bat0.health), (Some(87), Some(213), Some(91.)));
+        assert_eq!((bat0.time_to_empty, bat0.time_to_full), (Some(14400), None));
+        let bat1 = &power.batteries[1];
+        assert_eq!((bat1.health, bat1.time_to_empty, bat1.time_to_full), (Some(100.), None, Some(14400)));
+    }
+    #[test]
+    fn dmi() {
+        let root = fake_sysfs(
+            "dmi",
+            &[
+                ("sys_vendor", "LENOVO\n"),
+                ("product_name", "20KH006JPB\n"),
+                ("product_version", "ThinkPad X1 Carbon 6th\n"),
+                ("board_vendor", "LENOVO\n"),
+                ("board_name", "20KH006JPB\n"),
+                ("board_version", "Not Defined\n"),
+                ("bios_vendor", "LENOVO\n"),
+                ("bios_version", "N23ET75W (1.50 )\n"),
+                ("bios_date", "10/13/2020\n"),
+                ("chassis_vendor", "To Be Filled By O.E.M.\n"),
+                ("chassis_type", "10\n"),
+            ],
+        );
+        // unreadable for regular users
+        fs::create_dir(root.join("product_serial")).unwrap();
+        let dmi = procfs::_dmi(&root);
+        assert_eq!(dmi.sys_vendor.as_deref(), Some("LENOVO"));
+        assert_eq!(dmi.product_version.as_deref(), Some("ThinkPad X1 Carbon 6th"));
+        assert_eq!(dmi.bios_version.as_deref(), Some("N23ET75W (1.50 )"));
+        assert_eq!(dmi.chassis_type.as_deref(), Some("Notebook"));
+        assert_eq!((dmi.product_serial, dmi.product_uuid, dmi.chassis_vendor), (None, None, None));
+    }
+    #[test]
+    fn os_release() {
+        let os = procfs::_os_release(tests::OS_RELEASE);
+        assert_eq!(os.pretty_name, "Ubuntu 22.04.3 LTS");
+        assert_eq!(os.version_id.as_deref(), Some("22.04"));
+        assert_eq!(os.id_like, vec!["debian".to_string()]);
+        assert!(os.is_like("debian") && os.is_like("ubuntu") && !os.is_like("fedora"));
+        let lsb = procfs::_lsb_release(tests::LSB_RELEASE);
+        assert_eq!((lsb.id.as_str(), lsb.pretty_name.as_str()), ("linuxmint", "Linux Mint 21.2 Victoria"));
+        assert_eq!(procfs::_init_system("systemd", None, true), "systemd");
+        assert_eq!(procfs::_init_system("init", Some("openrc-init"), false), "openrc");
+        assert_eq!(procfs::_init_system("init", None, false), "sysvinit");
+        assert_eq!(procfs::_boot_time(1000, 400.7), 600);
+    }
+    #[test]
+    fn environment() {
+        let root = fake_sysfs("env", &[("proc/1/cgroup", "0::/\n")]);
+        let env = procfs::_environment(&root, &Dmi::default(), "flags\t\t: fpu vme sse2 ss\n", None);
+        assert!(env.is_bare_metal());
+        let dmi = Dmi {
+            sys_vendor: Some("QEMU".to_string()),
+            product_name: Some("Standard PC".to_string()),
+            ..Default::default()
+        };
+        let env = procfs::_environment(&root, &dmi, "flags\t\t: fpu vme hypervisor\n", Some("TCGTCGTCGTCG"));
+        assert_eq!(env.hypervisor, Some(Hypervisor::Qemu));
+        // kvm guests emulated by qemu report its dmi vendor
+        let env = procfs::_environment(&root, &dmi, "flags\t\t: fpu vme hypervisor\n", Some("KVMKVMKVM"));
+        assert_eq!(env.hypervisor, Some(Hypervisor::Kvm));
+        assert_eq!(procfs::_environment(&root, &dmi, "", None).hypervisor, Some(Hypervisor::Qemu));
+        assert_eq!(procfs::_environment(&root, &Dmi::default(), tests::CPU_INFO, None).hypervisor, None);
+        fs::write(root.join("proc/1/cgroup"), "0::/kubepods/besteffort/pod1f2e/4a5b\n").unwrap();
+        fs::write(root.join(".dockerenv"), "").unwrap();
+        let env = procfs::_environment(&root, &Dmi::default(), "flags\t\t: fpu hypervisor\n", Some("unknown sig"));
+        assert_eq!((env.container, env.hypervisor), (Some(Container::Kubernetes), Some(Hypervisor::Unknown)));
+        fs::write(root.join("proc/1/cgroup"), "0::/\n").unwrap();
+        assert_eq!(procfs::_environment(&root, &Dmi::default(), "", None).to_string(), "Docker container");
+        fs::remove_file(root.join(".dockerenv")).unwrap();
+        fs::write(root.join("proc/1/environ"), "PATH=/bin\0container=systemd-nspawn\0").unwrap();
+        assert_eq!(
+            procfs::_environment(&root, &Dmi::default(), "", None).container,
+            Some(Container::Other("systemd-nspawn".into()))
+        );
+    }
+    #[test]
+    fn cgroups() {
+        let hybrid = procfs::_proc_cgroups(tests::PROC_CGROUP_V1);
+        assert_eq!(hybrid.v1_path("cpuacct"), Some("/docker/4f1a"));
+        assert_eq!((hybrid.path(), hybrid.v2_path()), ("/docker/4f1a", Some("/")));
+        let root = fake_sysfs(
+            "cgroup-v1",
+            &[
+                ("memory/docker/4f1a/memory.usage_in_bytes", "52428800\n"),
+                ("memory/docker/4f1a/memory.limit_in_bytes", "9223372036854771712\n"),
+                ("cpu,cpuacct/docker/4f1a/cpuacct.usage", "1500000000\n"),
+                ("cpu,cpuacct/docker/4f1a/cpu.cfs_quota_us", "150000\n"),
+                ("cpu,cpuacct/docker/4f1a/cpu.cfs_period_us", "100000\n"),
+                ("blkio/docker/4f1a/blkio.throttle.io_service_bytes", "8:0 Read 4096\n8:0 Write 8192\nTotal 12288\n"),
+                ("blkio/docker/4f1a/blkio.throttle.io_serviced", "8:0 Read 1\n8:0 Write 2\nTotal 3\n"),
+                ("unified/pids.current", "3\n"),
+            ],
+        );
+        fs::rename(root.join("cpu,cpuacct"), root.join("cpuacct")).unwrap();
+        std::os::unix::fs::symlink("cpuacct", root.join("cpu")).unwrap();
+        let stats = procfs::_cgroup_stats(&root, &hybrid);
+        assert_eq!((stats.memory_current, stats.memory_max), (Some(52428800), None));
+        assert_eq!((stats.cpu_usage_usec, stats.cpu_limit()), (Some(1500000), Some(1.5)));
+        assert_eq!(stats.io, vec![CgroupIo { device: "8:0".into(), rbytes: 4096, wbytes: 8192, rios: 1, wios: 2 }]);
+        assert_eq!(stats.pids_current, Some(3));
+
+        let unified = procfs::_proc_cgroups("0::/system.slice/sshd.service\n");
+        assert_eq!(unified.unit(), Some("sshd.service"));
+        let root = fake_sysfs(
+            "cgroup-v2",
+            &[
+                ("cgroup.controllers", "cpu io memory pids\n"),
+                ("system.slice/sshd.service/memory.current", "7340032\n"),
+                ("system.slice/sshd.service/memory.max", "max\n"),
+                ("system.slice/sshd.service/cpu.stat", "usage_usec 2500\nuser_usec 2000\nsystem_usec 500\n"),
+                ("system.slice/sshd.service/cpu.max", "50000 100000\n"),
+                ("system.slice/sshd.service/pids.max", "512\n"),
+                ("system.slice/sshd.service/io.stat", "8:0 rbytes=10 wbytes=20 rios=1 wios=2 dbytes=0 dios=0\n"),
+            ],
+        );
+        let stats = procfs::_cgroup_stats(&root, &unified);
+        assert_eq!((stats.memory_current, stats.memory_max), (Some(7340032), None));
+        assert_eq!((stats.cpu_user_usec, stats.cpu_system_usec, stats.cpu_limit()), (Some(2000), Some(500), Some(0.5)));
+        assert_eq!((stats.pids_current, stats.pids_max), (None, Some(512)));
+        assert_eq!(stats.io, vec![CgroupIo { device: "8:0".into(), rbytes: 10, wbytes: 20, rios: 1, wios: 2 }]);
+    }
+    #[test]
+    fn resource_limits() {
+        let cgroups = procfs::_proc_cgroups("0::/kubepods/burstable/pod1/c1\n");
+        let root = fake_sysfs(
+            "limits",
+            &[
+                ("cgroup.controllers", "cpuset cpu memory\n"),
+                ("kubepods/burstable/pod1/memory.max", "536870912\n"),
+                ("kubepods/burstable/pod1/cpu.max", "250000 100000\n"),
+                ("kubepods/burstable/pod1/c1/memory.max", "max\n"),
+                ("kubepods/burstable/pod1/c1/cpu.max", "max 100000\n"),
+                ("kubepods/burstable/pod1/c1/cpuset.cpus.effective", "0-1,4\n"),
+            ],
+        );
+        let limits = procfs::_resource_limits(&root, cgroups.clone(), 16714952704, 8);
+        assert_eq!(limits, ResourceLimits { memory: Some(536870912), cpus: Some(2.5) });
+        fs::write(root.join("kubepods/burstable/pod1/cpu.max"), "max 100000\n").unwrap();
+        assert_eq!(procfs::_resource_limits(&root, cgroups.clone(), 16714952704, 8).cpus, Some(3.));
+        assert_eq!(procfs::_resource_limits(&root, cgroups, 268435456, 2), ResourceLimits::default());
+        assert_eq!(utils::parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+
+        // container without cgroup namespace has its own cgroup mounted as hierarchy root
+        let mounts = procfs::_cgroup_mounts(tests::MOUNTINFO_DOCKER);
+        assert_eq!(mounts.0.len(), 4);
+        let cgroups = procfs::_proc_cgroups("7:cpuset:/docker/4f1a\n4:memory:/docker/4f1a\n3:pids:/docker/4f1a/sub\n");
+        let relative = cgroups.relative_to(&mounts);
+        assert_eq!((relative.v1_path("memory"), relative.v1_path("pids")), (Some("/"), Some("/sub")));
+        let root = fake_sysfs(
+            "limits-docker",
+            &[
+                ("memory/memory.limit_in_bytes", "1073741824\n"),
+                ("cpuset/cpuset.cpus", "2-3\n"),
+                ("pids/sub/pids.current", "4\n"),
+            ],
+        );
+        // the parent walk still reaches the memory limit, but cpuset is read from the missing leaf
+        let limits = procfs::_resource_limits(&root, cgroups, 16714952704, 8);
+        assert_eq!(limits, ResourceLimits { memory: Some(1073741824), cpus: None });
+        let limits = procfs::_resource_limits(&root, relative.clone(), 16714952704, 8);
+        assert_eq!(limits, ResourceLimits { memory: Some(1073741824), cpus: Some(2.) });
+        assert_eq!(procfs::_cgroup_stats(&root, &relative).pids_current, Some(4));
+    }
+    #[test]
+    fn kernel_counters() {
+        let stat = procfs::_kernel_stat(tests::PROC_STAT_KERNEL);
+        assert_eq!((stat.ctxt, stat.intr, stat.softirq, stat.btime), (623288, 223698, 128991, 1592140515));
+        assert_eq!((stat.processes, stat.procs_running, stat.procs_blocked), (27031, 2, 1));
+        let vm = procfs::_vmstat(tests::VMSTAT);
+        assert_eq!((vm.pgfault, vm.pswpin, vm.oom_kill, vm.compact_stall), (15794026, 20, 1, 3));
+
+        let prev = KernelCounters { vmstat: vm.clone(), stat: stat.clone() };
+        let mut now = KernelCounters { vmstat: vm, stat };
+        now.stat.ctxt += 2000;
+        now.vmstat.pswpout += 10;
+        now.vmstat.oom_kill += 1;
+        let rates = now.rates(&prev, Duration::from_millis(500));
+        assert_eq!((rates.ctxsw, rates.forks, rates.oom_kills), (4000., 0., 1));
+        assert_eq!(rates.swap_out, 20. * utils::page_size() as f64);
+        assert_eq!(now.rates(&prev, Duration::from_secs(0)).ctxsw, 0.);
+
+        // sections that weren't collected are left out of serialized output
+        let info = serde_json::to_value(PcInfo { kernel: Some(rates), ..Default::default() }).unwrap();
+        assert!(info.get("kernel").is_some());
+        assert!(["usb", "interrupts", "softirqs", "numa_placement"].iter().all(|key| info.get(key).is_none()));
+    }
+    #[test]
+    fn interrupts() {
+        let mut irqs = procfs::_interrupts(tests::INTERRUPTS);
+        assert_eq!(irqs.cpus, vec![0, 1, 3]);
+        let nic = irqs.get("27").unwrap();
+        assert_eq!(nic.counts, vec![12, 9800134, 3]);
+        assert_eq!(nic.chip.as_deref(), Some("PCI-MSI"));
+        assert_eq!(nic.hwirq.as_deref(), Some("524288-edge"));
+        assert_eq!(nic.devices, vec!["enp5s0-rx-0".to_string(), "enp5s0-tx-0".to_string()]);
+        let old = irqs.get("9").unwrap();
+        assert_eq!((old.chip.as_deref(), old.hwirq.as_deref()), (Some("IO-APIC-fasteoi"), None));
+        let timer = irqs.get("11").unwrap();
+        assert_eq!((timer.chip.as_deref(), timer.hwirq.as_deref()), (Some("GICv3"), Some("27 Level")));
+        assert_eq!(timer.devices, vec!["arch_timer".to_string()]);
+        assert_eq!(irqs.get("ERR").unwrap().counts, vec![0]);
+        assert_eq!(irqs.get("LOC").unwrap().devices, vec!["Local timer interrupts".to_string()]);
+        assert_eq!(busiest_cpu(&irqs.cpus, &[12., 9800134., 3.]).map(|(cpu, _)| cpu), Some(1));
+        assert_eq!(busiest_cpu(&irqs.cpus, &[0., 0., 0.]), None);
+
+        let root = fake_sysfs("irq", &[("27/smp_affinity_list", "1,3\n"), ("27/effective_affinity_list", "1\n")]);
+        procfs::_irq_affinity(&root, &mut irqs);
+        let nic = irqs.get("27").unwrap();
+        assert_eq!((nic.affinity.clone(), nic.effective_affinity.clone()), (vec![1, 3], vec![1]));
+        assert!(irqs.get("LOC").unwrap().affinity.is_empty());
+
+        let soft = procfs::_interrupts(tests::SOFTIRQS);
+        let mut now = soft.clone();
+        now.interrupts[1].counts[2] += 500;
+        let rates = now.rates(&soft, Duration::from_millis(500));
+        assert_eq!(rates.rates[1].irq, "NET_RX");
+        assert_eq!(rates.rates[1].per_cpu, vec![0., 0., 1000.]);
+        assert_eq!(rates.per_cpu(), vec![0., 0., 1000.]);
+    }
+    #[test]
+    fn numa_nodes() {
+        let root = fake_sysfs(
+            "numa",
+            &[
+                ("node0/cpulist", "0-3\n"),
+                ("node0/distance", "10 21\n"),
+                ("node0/meminfo", tests::NODE_MEMINFO),
+                ("node1/cpulist", "4-7\n"),
+                ("node1/distance", "21 10\n"),
+                ("online", "0-1\n"),
+            ],
+        );
+        let numa = procfs::_numa_nodes(&root).unwrap();
+        assert!(numa.is_numa());
+        assert_eq!(numa.nodes.iter().map(|n| n.id).collect::<Vec<u32>>(), vec![0, 1]);
+        let node = numa.get(0).unwrap();
+        assert_eq!(node.cpus, vec![0, 1, 2, 3]);
+        assert_eq!((node.mem_total, node.mem_free, node.mem_used), (5734136 * 1024, 3441248 * 1024, 2292888 * 1024));
+        assert_eq!((node.anon_pages, node.hugepages_total, node.hugepages_free), (175296 * 1024, 16, 4));
+        assert_eq!((numa.distance(0, 1), numa.distance(1, 1), numa.distance(0, 2)), (Some(21), Some(10), None));
+        assert_eq!((numa.node_of_cpu(5), numa.node_of_cpu(8)), (Some(1), None));
+        assert_eq!(numa.get(1).unwrap().mem_ratio(), None);
+
+        let nodes = Process::_numa_maps(tests::NUMA_MAPS);
+        assert_eq!(nodes.get(&0), Some(&((2 + 6) * 4096 + 2048 * 1024)));
+        assert_eq!(nodes.get(&1), Some(&(30 * 4096)));
+        let placement = NumaPlacement { pid: 1, name: "init".to_string(), nodes };
+        assert_eq!(placement.preferred_node().map(|(node, _)| node), Some(0));
+    }
+    #[test]
+    fn loadavg() {
+        let load = LoadAvg { one: 0.52, five: 0.61, fifteen: 0.7, running_tasks: 2, total_tasks: 812, last_pid: 31337 };
+        assert_eq!(procfs::_loadavg(tests::LOAD_AVG), load);
+        assert_eq!(load.per_core(2), (0.26, 0.305, 0.35));
     }
     #[test]
     fn mem_free() {
-        assert_eq!(procfs::_mem(Memory::MemFree, &tests::MEM_INFO), 10178555904);
+        assert_eq!(procfs::_mem(Memory::MemFree, tests::MEM_INFO), 10178555904);
     }
     #[test]
     fn mem_total() {
-        assert_eq!(procfs::_mem(Memory::MemTotal, &tests::MEM_INFO), 16714952704);
+        assert_eq!(procfs::_mem(Memory::MemTotal, tests::MEM_INFO), 16714952704);
     }
     #[test]
     fn swap_free() {
-        assert_eq!(procfs::_mem(Memory::SwapFree, &tests::MEM_INFO), 0);
+        assert_eq!(procfs::_mem(Memory::SwapFree, tests::MEM_INFO), 0);
     }
     #[test]
     fn swap_total() {
-        assert_eq!(procfs::_mem(Memory::SwapTotal, &tests::MEM_INFO), 0);
+        assert_eq!(procfs::_mem(Memory::SwapTotal, tests::MEM_INFO), 0);
     }
     #[test]
     fn ipv4_addr() {
         assert_eq!(
-            procfs::_ipv4_addr("wlan0", &tests::ROUTE, &tests::FIB_TRIE).unwrap(),
+            procfs::_ipv4_addr("wlan0", tests::ROUTE, tests::FIB_TRIE).unwrap(),
             Ipv4Addr::new(192, 168, 8, 201)
         );
-        assert_eq!(procfs::_ipv4_addr("lo", &tests::ROUTE, &tests::FIB_TRIE).unwrap(), Ipv4Addr::new(127, 0, 0, 1))
+        assert_eq!(procfs::_ipv4_addr("lo", tests::ROUTE, tests::FIB_TRIE).unwrap(), Ipv4Addr::new(127, 0, 0, 1))
     }
     #[test]
     fn ipv6_addr() {
         assert_eq!(
-            procfs::_ipv6_addr("wlan0", &tests::IF_INET6).unwrap(),
+            procfs::_ipv6_addr("wlan0", tests::IF_INET6).unwrap(),
             Ipv6Addr::new(0xfe80, 0, 0, 0, 0xd81, 0x2a0d, 0x8467, 0xda1c)
         );
     }
     #[test]
     fn uptime() {
-        assert_eq!(procfs::_uptime(&tests::UPTIME), 52662.34)
+        assert_eq!(procfs::_uptime(tests::UPTIME), 52662.34)
     }
     #[test]
-    fn graphics_card() {
-        assert_eq!(procfs::_graphics_card(&tests::LSPCI), "NVIDIA Corporation GK106 [GeForce GTX 660] (rev a1)")
+    fn pci_devices() {
+        let root = fake_sysfs(
+            "pci",
+            &[
+                ("0000:09:00.0/vendor", "0x10de\n"),
+                ("0000:09:00.0/device", "0x11c0\n"),
+                ("0000:09:00.0/subsystem_vendor", "0x1043\n"),
+                ("0000:09:00.0/subsystem_device", "0x842e\n"),
+                ("0000:09:00.0/class", "0x030000\n"),
+                ("0000:09:00.0/revision", "0xa1\n"),
+                ("0000:09:00.0/numa_node", "-1\n"),
+                ("0000:09:00.0/current_link_speed", "8.0 GT/s PCIe\n"),
+                ("0000:09:00.0/current_link_width", "16\n"),
+                ("0000:01:00.0/vendor", "0x10de\n"),
+                ("0000:01:00.0/device", "0x1eb8\n"),
+                ("0000:01:00.0/class", "0x030200\n"),
+                ("0000:01:00.0/numa_node", "1\n"),
+                ("0000:00:1f.3/vendor", "0x8086\n"),
+                ("0000:00:1f.3/device", "0xa348\n"),
+                ("0000:00:1f.3/class", "0x040300\n"),
+            ],
+        );
+        let mut pci = procfs::_pci_devices(&root).unwrap();
+        let ids = PciIds::parse(tests::PCI_IDS);
+        pci.devices.iter_mut().for_each(|dev| ids.resolve(dev));
+        assert_eq!(pci.devices.len(), 3);
+        let gpus = pci.gpus();
+        assert_eq!(gpus.iter().map(|gpu| &gpu.address[..]).collect::<Vec<_>>(), vec!["0000:01:00.0", "0000:09:00.0"]);
+        assert_eq!(gpus[0].name(), "NVIDIA Corporation TU104GL [Tesla T4]");
+        assert_eq!(procfs::_graphics_card(&pci), gpus[0].name());
+        assert_eq!(procfs::_graphics_card(&PciDevices::default()), "");
+        assert_eq!((gpus[0].numa_node, gpus[0].class_name.as_deref()), (Some(1), Some("3D controller")));
+        let gtx = gpus[1];
+        assert_eq!(gtx.name(), "NVIDIA Corporation GK106 [GeForce GTX 660]");
+        assert_eq!((gtx.subsystem_vendor_id, gtx.subsystem_device_id), (Some(0x1043), Some(0x842e)));
+        assert_eq!(gtx.subsystem_name.as_deref(), Some("GTX660-DC2O-2GD5"));
+        assert_eq!((gtx.revision, gtx.numa_node), (Some(0xa1), None));
+        assert_eq!((gtx.link_speed.as_deref(), gtx.link_width), (Some("8.0 GT/s PCIe"), Some(16)));
+        assert_eq!(gtx.class_name.as_deref(), Some("VGA compatible controller"));
+        let audio = &pci.devices[0];
+        assert!(!audio.is_gpu());
+        assert_eq!(
+            (audio.name(), audio.class_name.as_deref()),
+            ("Intel Corporation a348".to_string(), Some("Multimedia controller"))
+        );
+    }
+    #[test]
+    fn usb_devices() {
+        let root = fake_sysfs(
+            "usb",
+            &[
+                ("usb1/busnum", "1\n"),
+                ("usb1/devnum", "1\n"),
+                ("usb1/idVendor", "1d6b\n"),
+                ("usb1/idProduct", "0002\n"),
+                ("usb1/speed", "480\n"),
+                ("1-0:1.0/bInterfaceClass", "09\n"),
+                ("1-1/busnum", "1\n"),
+                ("1-1/devnum", "2\n"),
+                ("1-1/idVendor", "05e3\n"),
+                ("1-1/idProduct", "0610\n"),
+                ("1-1.2/busnum", "1\n"),
+                ("1-1.2/devnum", "4\n"),
+                ("1-1.2/idVendor", "046d\n"),
+                ("1-1.2/idProduct", "c52b\n"),
+                ("1-1.2/manufacturer", "Logitech\n"),
+                ("1-1.2/product", "USB Receiver\n"),
+                ("1-1.2/speed", "12\n"),
+                ("1-1.10/busnum", "1\n"),
+                ("1-1.10/idVendor", "1050\n"),
+                ("1-1.10/idProduct", "0407\n"),
+                ("1-4/busnum", "1\n"),
+                ("1-4/devnum", "3\n"),
+                ("1-5/idVendor", "0bda\n"),
+                ("1-1.2:1.0/bInterfaceClass", "03\n"),
+                ("usb2/busnum", "2\n"),
+            ],
+        );
+        std::os::unix::fs::symlink("../../../../bus/usb/drivers/hub", root.join("1-0:1.0/driver")).unwrap();
+        std::os::unix::fs::symlink("../../../../bus/usb/drivers/usbhid", root.join("1-1.2:1.0/driver")).unwrap();
+        let usb = procfs::_usb_devices(&root).unwrap();
+        assert_eq!(usb.devices.iter().map(|d| &d.name[..]).collect::<Vec<_>>(), vec!["usb1", "usb2"]);
+        let hub = &usb.devices[0];
+        // 1-5 has no busnum and still hangs off bus 1
+        assert_eq!(hub.children.iter().map(|d| &d.name[..]).collect::<Vec<_>>(), vec!["1-1", "1-4", "1-5"]);
+        assert_eq!((hub.drivers.clone(), hub.children[2].bus), (vec!["hub".to_string()], 1));
+        let ports = hub.children[0].children.iter().map(|d| &d.port[..]).collect::<Vec<_>>();
+        assert_eq!(ports, vec!["1.2", "1.10"]);
+        let receiver = usb.find(0x046d, 0xc52b).unwrap();
+        assert_eq!((receiver.bus, receiver.devnum, receiver.speed.as_deref()), (1, 4, Some("12")));
+        assert_eq!(
+            (receiver.manufacturer.as_deref(), receiver.product.as_deref()),
+            (Some("Logitech"), Some("USB Receiver"))
+        );
+        assert_eq!(receiver.drivers, vec!["usbhid".to_string()]);
+        assert!(usb.find(0x1050, 0x0407).is_some());
+        assert!(usb.find(0x1050, 0x0408).is_none());
     }
     #[test]
     fn storage_partitions() {
@@ -76,7 +704,7 @@ mod gets {
                 mountpoint: "".to_string(),
             },
         ];
-        assert_eq!(procfs::_storage_partitions("sdd", &tests::STOR_DEV, &tests::STOR_MOUNTS), partitions)
+        assert_eq!(procfs::_storage_partitions("sdd", tests::STOR_DEV, tests::STOR_MOUNTS), partitions)
     }
     #[test]
     fn storage_devices() {
@@ -146,7 +774,7 @@ mod gets {
             Storage { major: 8, minor: 64, size: 500107862016, name: "sde".to_string(), partitions: sde_partitions },
         ];
         let storages = Storages { storage_devices: devices };
-        assert_eq!(procfs::_storage_devices(&tests::STOR_DEV, &tests::STOR_MOUNTS), storages);
+        assert_eq!(procfs::_storage_devices(tests::STOR_DEV, tests::STOR_MOUNTS), storages);
     }
     #[test]
     fn network_devices() {
@@ -183,7 +811,7 @@ mod gets {
             ],
         };
         assert_eq!(
-            procfs::_network_devs(&tests::NET_DEV, &tests::ROUTE, &tests::FIB_TRIE, &tests::IF_INET6).unwrap(),
+            procfs::_network_devs(tests::NET_DEV, tests::ROUTE, tests::FIB_TRIE, tests::IF_INET6).unwrap(),
             net_dev
         )
     }
@@ -196,21 +824,260 @@ mod gets {
             state: ProcessState::Sleeping,
             ppid: 5868,
             pgrp: 17008,
+            session: 5868,
+            tty_nr: 34823,
             utime: 1169,
             stime: 4309,
+            priority: 20,
+            nice: 0,
+            num_threads: 1,
+            starttime: 3236493,
+            processor: 6,
+            rt_priority: 0,
+            policy: 0,
             size: 2926,
             resident: 1266,
             shared: 827,
-            uid: 0,
-            gid: 0,
+            uid: 1000,
+            euid: 0,
+            suid: 0,
+            fsuid: 0,
+            gid: 100,
+            egid: 100,
+            sgid: 100,
+            fsgid: 100,
+            groups: vec![10, 100, 998],
+            oom_score: 0,
+            oom_score_adj: 0,
+            exe: None,
+            cwd: None,
+            root: None,
         };
 
         let mut proc = Process::default();
-        proc.parse_proc_stat(&PROC_STAT).unwrap();
-        proc.parse_proc_statm(&PROC_STATM).unwrap();
-        proc.cmd = Process::_cmd(&PROC_CMDLINE);
+        proc.parse_proc_stat(PROC_STAT).unwrap();
+        proc.parse_proc_statm(PROC_STATM).unwrap();
+        proc.parse_proc_status(PROC_STATUS).unwrap();
+        proc.cmd = Process::_cmd(PROC_CMDLINE);
 
         assert_eq!(base, proc);
+        assert_eq!((proc.tty().as_deref(), proc.sched_policy()), (Some("pts/7"), "SCHED_OTHER"));
+        assert_eq!(proc.start_time(1_600_000_000), 1_600_000_000 + 3236493 / utils::clk_tick());
+        let users = UserCache::parse(tests::PASSWD, tests::GROUP);
+        assert_eq!(
+            (users.user(1000), users.user(0), users.user(4242)),
+            ("wojtas".into(), "root".into(), "4242".into())
+        );
+        assert_eq!(proc.groups.iter().map(|g| users.group(*g)).collect::<Vec<_>>(), vec!["wheel", "users", "docker"]);
+        let environ = Process::_environ("HOME=/root\u{0}LS_COLORS=di=1;34\u{0}");
+        assert_eq!(environ[1], ("LS_COLORS".to_string(), "di=1;34".to_string()));
+    }
+    #[test]
+    fn process_filter() {
+        let mut proc = Process::default();
+        proc.parse_proc_stat(PROC_STAT).unwrap();
+        proc.parse_proc_statm(PROC_STATM).unwrap();
+        proc.cmd = Process::_cmd(PROC_CMDLINE);
+        let uptime = 3236493. / utils::clk_tick() as f64 + 100.;
+        // 100 seconds after start so cpu usage in percent equals cpu time in seconds
+        assert!((proc.cpu_usage(uptime) - proc.cpu_time()).abs() < 1e-6);
+        let filter = ProcessFilter::new().name("^htop").unwrap().ppid(5868).state("S".parse().unwrap());
+        assert!(filter.matches(&proc, uptime));
+        assert!(ProcessFilter::new().cmd("--no-color$").unwrap().user("0").matches(&proc, uptime));
+        assert!(!ProcessFilter::new().min_rss(proc.resident() + 1).matches(&proc, uptime));
+        assert!(!ProcessFilter::new().state(ProcessState::Zombie).matches(&proc, uptime));
+        assert!(ProcessFilter::new().name("(").is_err());
+        assert_eq!("tracingstop".parse::<ProcessState>().unwrap(), ProcessState::TracingStop);
+        assert!("q".parse::<ProcessState>().is_err());
+
+        let mut ps = vec![
+            Process { pid: 3, resident: 10, starttime: 5, ..Default::default() },
+            Process { pid: 1, resident: 30, starttime: 1, ..Default::default() },
+            Process { pid: 2, resident: 20, starttime: 9, ..Default::default() },
+        ];
+        "rss".parse::<ProcessSort>().unwrap().sort(&mut ps, uptime);
+        assert_eq!(ps.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1, 2, 3]);
+        ProcessSort::Start.sort(&mut ps, uptime);
+        assert_eq!(ps.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1, 3, 2]);
+    }
+    #[test]
+    fn process_control() {
+        assert_eq!(
+            ("sigterm".parse::<Signal>().unwrap(), "9".parse::<Signal>().unwrap()),
+            (Signal::Term, Signal::Kill)
+        );
+        assert!("SIGWINCH".parse::<Signal>().is_err());
+
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let proc = Process::new(child.id()).unwrap();
+        assert!(proc.is_running());
+        proc.renice(5).unwrap();
+        assert_eq!(Process::new(child.id()).unwrap().nice, 5);
+        proc.set_affinity(&[0]).unwrap();
+        assert_eq!(proc.affinity().unwrap(), vec![0]);
+
+        // the same pid with different start time is another process
+        let reused = Process { starttime: proc.starttime + 1, ..Process::new(child.id()).unwrap() };
+        assert!(!reused.is_running() && reused.signal(Signal::Term).is_err());
+
+        assert!(proc.terminate(Duration::from_secs(5)).unwrap());
+        assert!(!proc.is_running() && proc.kill().is_err());
+        child.wait().unwrap();
+    }
+    #[test]
+    fn process_watcher() {
+        use crate::watch::*;
+        let proc =
+            |pid: u32, starttime: u64, name: &str| Process { pid, starttime, name: name.into(), ..Default::default() };
+        let mut watcher = ProcessWatcher::empty(Duration::from_millis(10));
+        assert_eq!(watcher.diff(vec![proc(1, 1, "init"), proc(20, 5, "make")]).len(), 2);
+
+        // pid 20 reused by a new process and make exec'd into cc
+        let events = watcher.diff(vec![proc(1, 1, "init"), proc(20, 9, "cc1"), proc(21, 6, "make")]);
+        assert_eq!(
+            events,
+            vec![
+                ProcessEvent::Started(proc(20, 9, "cc1")),
+                ProcessEvent::Started(proc(21, 6, "make")),
+                ProcessEvent::Exited(proc(20, 5, "make")),
+            ]
+        );
+        let events = watcher.diff(vec![proc(1, 1, "init"), proc(21, 6, "cc")]);
+        assert_eq!(events[0], ProcessEvent::Changed { old: Box::new(proc(21, 6, "make")), new: proc(21, 6, "cc") });
+        assert_eq!(events[1], ProcessEvent::Exited(proc(20, 9, "cc1")));
+        assert!(watcher.diff(vec![proc(1, 1, "init"), proc(21, 6, "cc")]).is_empty());
+
+        // kworkers renaming themselves and state flips alone are not changes
+        let kworker = Process { ppid: 2, ..proc(30, 2, "kworker/0:1-events") };
+        watcher.diff(vec![proc(1, 1, "init"), proc(21, 6, "cc"), kworker.clone()]);
+        let sleeping = Process { state: ProcessState::Sleeping, ..proc(21, 6, "cc") };
+        let renamed = Process { name: "kworker/0:1-mm_percpu_wq".into(), ..kworker };
+        assert!(watcher.diff(vec![proc(1, 1, "init"), sleeping, renamed]).is_empty());
+
+        // netlink header, connector header with 40 bytes of payload, what, cpu, timestamp and event data
+        let event = |what: u32, data: [u32; 4]| {
+            let words = [68, 3, 0, 0, 1, 1, 0, 0, 40, what, 0, 0, 0, data[0], data[1], data[2], data[3]];
+            words.iter().flat_map(|w| w.to_ne_bytes().to_vec()).collect::<Vec<u8>>()
+        };
+        // 4200 forks 4242, which starts thread 4243, execs and exits after its thread
+        let buf = [
+            event(0x1, [4200, 4200, 4242, 4242]),
+            event(0x1, [4242, 4242, 4243, 4242]),
+            event(0x2, [4242, 4242, 0, 0]),
+            event(0x8000_0000, [4243, 4242, 0, 0]),
+            event(0x8000_0000, [4242, 4242, 0, 9]),
+        ]
+        .concat();
+        let events = _connector_events(&buf);
+        assert_eq!(events, vec![ConnectorEvent::Fork(4242), ConnectorEvent::Exec(4242), ConnectorEvent::Exit(4242)]);
+        assert!(_connector_events(&buf[..60]).is_empty());
+
+        let mut events = ProcessWatcher::new(Duration::from_millis(10)).unwrap().into_stream();
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let started = async_std::task::block_on(async_std::future::timeout(Duration::from_secs(10), async {
+            use async_std::prelude::*;
+            while let Some(event) = events.next().await {
+                match event.unwrap() {
+                    ProcessEvent::Started(p) if p.pid == child.id() => return true,
+                    _ => {}
+                }
+            }
+            false
+        }));
+        assert_eq!(started, Ok(true));
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // exit of a process matching the cgroup filter is reported although its cgroup can't be read anymore
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let p = Process::new(child.id()).unwrap();
+        let cgroup = p.cgroups().unwrap().path().to_string();
+        let other = Process { pid: 999_999, starttime: 1, ..Default::default() };
+        let mut watcher = ProcessWatcher::empty(Duration::from_millis(10)).filter(ProcessFilter::new().cgroup(&cgroup));
+        assert_eq!(watcher.diff(vec![p.clone(), other.clone()]), vec![ProcessEvent::Started(p.clone())]);
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(p.cgroups().is_err());
+        assert_eq!(watcher.diff(vec![]), vec![ProcessEvent::Exited(p)]);
+    }
+    #[test]
+    fn process_security() {
+        let caps = procfs::_capabilities(tests::PROC_STATUS_CAPS);
+        assert!(caps.is_privileged() && caps.no_new_privs);
+        assert_eq!((caps.seccomp, caps.seccomp_filters), (Seccomp::Filter, Some(1)));
+        assert_eq!(caps.effective.names(), vec!["CAP_NET_BIND_SERVICE", "CAP_NET_RAW"]);
+        assert!(caps.bounding.has("sys_admin") && !caps.effective.has("CAP_SYS_ADMIN"));
+        assert_eq!(CapabilitySet(1 << 63).names(), vec!["CAP_63"]);
+        assert!(!procfs::_capabilities("CapEff:\t0000000000000000\n").is_privileged());
+
+        let root = fake_sysfs("ns", &[]);
+        fs::create_dir_all(&root).unwrap();
+        std::os::unix::fs::symlink("net:[4026532008]", root.join("net")).unwrap();
+        std::os::unix::fs::symlink("pid:[4026531836]", root.join("pid")).unwrap();
+        let ns = procfs::_namespaces(&root).unwrap();
+        assert_eq!((ns.net, ns.pid, ns.uts), (Some(4026532008), Some(4026531836), None));
+        // an unreadable link is reported instead of looking unsupported
+        fs::write(root.join("ipc"), "").unwrap();
+        assert!(procfs::_namespaces(&root).is_err());
+        let init = Namespaces { net: Some(4026531833), pid: Some(4026531836), ..Default::default() };
+        assert_eq!(ns.diff(&init), vec!["net"]);
+    }
+    #[test]
+    fn process_limits() {
+        let limits = Process::_limits(tests::PROC_LIMITS);
+        let nofile = limits.get("Max open files").unwrap();
+        assert_eq!((nofile.soft, nofile.hard, nofile.unit.as_deref()), (Some(1024), Some(524288), Some("files")));
+        let cpu = limits.get("Max cpu time").unwrap();
+        assert_eq!((cpu.soft, cpu.hard), (None, None));
+        assert_eq!(limits.get("Max nice priority").unwrap().unit, None);
+        assert_eq!(limits.0.len(), 4);
+
+        let mut ps = vec![
+            Process { pid: 1, oom_score: 0, ..Default::default() },
+            Process { pid: 2, oom_score: 690, ..Default::default() },
+            Process { pid: 3, oom_score: 12, ..Default::default() },
+        ];
+        "oom".parse::<ProcessSort>().unwrap().sort(&mut ps, 0.);
+        assert_eq!(ps.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![2, 3, 1]);
+    }
+    #[test]
+    fn byte_size() {
+        assert_eq!(utils::parse_byte_size("100m").unwrap(), 100 * 1024 * 1024);
+        assert_eq!(utils::parse_byte_size("1.5G").unwrap(), 1536 * 1024 * 1024);
+        assert_eq!(utils::parse_byte_size("512 KiB").unwrap(), 512 * 1024);
+        assert_eq!(utils::parse_byte_size("4096").unwrap(), 4096);
+        for invalid in &["", "abc", "10x", "1.2.3M", "-5M", "G"] {
+            assert!(utils::parse_byte_size(invalid).is_err(), "{} should be rejected", invalid);
+        }
+    }
+    #[test]
+    fn seconds() {
+        assert_eq!(utils::parse_seconds("0.5").unwrap(), Duration::from_millis(500));
+        for invalid in &["-1", "NaN", "inf", "soon"] {
+            assert!(utils::parse_seconds(invalid).is_err(), "{} should be rejected", invalid);
+        }
+        assert!(Signal::Term.is_termination() && !Signal::Hup.is_termination());
+    }
+    #[test]
+    fn process_memory() {
+        let rollup = Process::_smaps(SMAPS_ROLLUP);
+        let total = rollup[0].usage.unwrap();
+        assert_eq!((total.pss, total.uss(), total.file_backed()), (494 * 1024, 140 * 1024, 1356 * 1024));
+        assert_eq!(rollup[0].path.as_deref(), Some("[rollup]"));
+
+        let maps = Process::_smaps(SMAPS);
+        assert_eq!(maps.len(), 3);
+        assert_eq!(maps[0].path.as_deref(), Some("/usr/lib/my lib.so"));
+        assert_eq!((maps[0].perms.as_str(), maps[0].offset, maps[0].inode), ("r-xp", 0x1000, 317783));
+        assert_eq!((maps[1].path.as_deref(), maps[1].usage.unwrap().uss()), (None, 132 * 1024));
+        let mut total = MemoryUsage::default();
+        for usage in maps.iter().filter_map(|m| m.usage) {
+            total += usage;
+        }
+        let detail = MemoryDetail { total, mappings: maps };
+        assert_eq!((detail.total.rss, detail.total.swap), (148 * 1024, 12 * 1024));
+        assert_eq!(detail.by_path()[0], ("[anon]".to_string(), 132 * 1024));
+        assert_eq!(MemoryMapping { start: 0x2000, end: 0x1000, ..Default::default() }.size(), 0);
     }
 }
 
@@ -550,6 +1417,47 @@ cache_alignment : 64
 address sizes   : 43 bits physical, 48 bits virtual
 power management: ts ttp tm hwpstate eff_freq_ro [13] [14]";
 
+static CPU_INFO_ARM: &str = "processor\t: 0
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+
+processor\t: 1
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+
+processor\t: 2
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+
+processor\t: 3
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x0
+CPU part\t: 0xd08
+CPU revision\t: 3
+
+Hardware\t: BCM2835
+Revision\t: c03111
+Serial\t\t: 10000000a3b2c1d0
+Model\t\t: Raspberry Pi 4 Model B Rev 1.1";
+
 static MEM_INFO: &str = "MemTotal:       16323196 kB
 MemFree:         9939996 kB
 MemAvailable:   12560252 kB
@@ -651,6 +1559,92 @@ Local:
            |-- 192.168.8.255
               /32 link BROADCAST";
 
+static LOAD_AVG: &str = "0.52 0.61 0.70 2/812 31337\n";
+
+static OS_RELEASE: &str = "# comment
+PRETTY_NAME=\"Ubuntu 22.04.3 LTS\"
+NAME=\"Ubuntu\"
+VERSION_ID=\"22.04\"
+ID=ubuntu
+ID_LIKE=debian
+";
+
+static LSB_RELEASE: &str = "DISTRIB_ID=LinuxMint
+DISTRIB_RELEASE=21.2
+DISTRIB_CODENAME=victoria
+DISTRIB_DESCRIPTION=\"Linux Mint 21.2 Victoria\"
+";
+
+static PROC_CGROUP_V1: &str = "12:pids:/docker/4f1a
+6:cpu,cpuacct:/docker/4f1a
+4:memory:/docker/4f1a
+3:blkio:/docker/4f1a
+1:name=systemd:/docker/4f1a
+0::/
+";
+
+static MOUNTINFO_DOCKER: &str = "612 598 0:64 / / rw,relatime master:318 - overlay overlay rw,lowerdir=/l
+620 618 0:29 /docker/4f1a /sys/fs/cgroup/cpuset ro,nosuid,nodev,noexec,relatime master:12 - cgroup cgroup rw,cpuset
+621 618 0:32 /docker/4f1a /sys/fs/cgroup/memory ro,nosuid,nodev,noexec,relatime master:15 - cgroup cgroup rw,memory
+622 618 0:33 /docker/4f1a /sys/fs/cgroup/pids ro,nosuid,nodev,noexec,relatime master:16 - cgroup cgroup rw,pids
+623 618 0:34 / /sys/fs/cgroup/unified ro,nosuid,nodev,noexec,relatime - cgroup2 cgroup2 rw,nsdelegate
+";
+
+static PROC_STAT_KERNEL: &str = "cpu  10132153 290696 3084719 46828483 16683 0 25195 0 0 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0
+intr 223698 0 9 0 0 0 0 3 0 1 0 0 0 0
+ctxt 623288
+btime 1592140515
+processes 27031
+procs_running 2
+procs_blocked 1
+softirq 128991 0 54333 3 4509 0 0 18 0 11 70117
+";
+
+static NODE_MEMINFO: &str = "Node 0 MemTotal:        5734136 kB
+Node 0 MemFree:         3441248 kB
+Node 0 MemUsed:         2292888 kB
+Node 0 FilePages:       1879360 kB
+Node 0 AnonPages:        175296 kB
+Node 0 HugePages_Total:    16
+Node 0 HugePages_Free:      4
+";
+
+static NUMA_MAPS: &str = "55ff0f381000 default file=/usr/bin/head mapped=2 N0=2 kernelpagesize_kB=4
+55ff0f383000 interleave:0-1 anon=36 dirty=36 N0=6 N1=30 kernelpagesize_kB=4
+7f2a00000000 bind:0 file=/dev/hugepages/db huge dirty=1 N0=1 kernelpagesize_kB=2048
+7ffd5e2d1000 default stack anon=1 dirty=1
+";
+
+static INTERRUPTS: &str = "           CPU0       CPU1       CPU3       
+  0:         44          0          0   IO-APIC   2-edge      timer
+  9:          0          3          0   IO-APIC-fasteoi   acpi
+ 27:         12    9800134          3   PCI-MSI 524288-edge      enp5s0-rx-0, enp5s0-tx-0
+ 11:     613122     611037     610542     GICv3  27 Level     arch_timer
+NMI:          1          2          0   Non-maskable interrupts
+LOC:    5110284    5101031    5102037   Local timer interrupts
+ERR:          0
+";
+
+static SOFTIRQS: &str = "                    CPU0       CPU1       CPU3       
+          HI:          0          1          0
+      NET_RX:        120        344      10290
+       TIMER:     827741     828102     827377
+";
+
+static VMSTAT: &str = "nr_free_pages 883986
+pgpgin 1036466
+pgpgout 5812488
+pswpin 20
+pswpout 0
+pgfault 15794026
+pgmajfault 882
+oom_kill 1
+compact_stall 3
+compact_fail 0
+compact_success 3
+";
+
 static UPTIME: &str = "52662.34 619766.90";
 
 static IF_INET6: &str = "fe800000000000000d812a0d8467da1c 04 40 20 80    wlan0
@@ -658,7 +1652,22 @@ fe800000000000009879b5d0240418bf 01 40 20 80       lo
 fe80000000000000f17b7100b5a1f781 05 40 20 80     tun0
 00000000000000000000000000000001 01 80 10 80       lo";
 
-static LSPCI: &str = "09:00.0 VGA compatible controller: NVIDIA Corporation GK106 [GeForce GTX 660] (rev a1)";
+static PCI_IDS: &str = "#
+#	List of PCI ID's
+#
+10de  NVIDIA Corporation
+	11c0  GK106 [GeForce GTX 660]
+		1043 842e  GTX660-DC2O-2GD5
+	1eb8  TU104GL [Tesla T4]
+8086  Intel Corporation
+	0d57  Ice Lake IEH
+C 03  Display controller
+	00  VGA compatible controller
+		00  VGA controller
+	02  3D controller
+	80  Display controller
+C 04  Multimedia controller
+	01  Multimedia audio controller";
 
 static STOR_DEV: &str = "major minor  #blocks  name
    8       32  312570167 sdc
@@ -725,6 +1734,78 @@ static NET_DEV: &str = "Inter-|   Receive
 
 static PROC_STAT: &str = "17008 (htop: test) S 5868 17008 5868 34823 17008 4194560 557 0 0 0 1169 4309 0 0 20 0 1 0 3236493 11984896 1266 18446744073709551615 94222821875712 94222821963109 140735121616416 0 0 0 0 0 134759430 0 0 0 17 6 0 0 0 0 0 94222822005680 94222822021792 94222838616064 140735121623809 140735121623814 140735121623814 140735121625066 0";
 
+static SMAPS_ROLLUP: &str = "55691dc0f000-7fff16b4d000 ---p 00000000 00:00 0                          [rollup]
+Rss:                1456 kB
+Pss:                 494 kB
+Pss_Anon:            100 kB
+Pss_File:            323 kB
+Shared_Clean:       1104 kB
+Shared_Dirty:          0 kB
+Private_Clean:        40 kB
+Private_Dirty:       100 kB
+Anonymous:           100 kB
+Swap:                  0 kB
+";
+
+static SMAPS: &str = "7f2a1c000000-7f2a1c004000 r-xp 00001000 fe:00 317783                     /usr/lib/my lib.so
+Size:                 16 kB
+Rss:                  12 kB
+Pss:                   6 kB
+Private_Clean:         0 kB
+VmFlags: rd ex mr mw me
+7f2a1c004000-7f2a1c025000 rw-p 00000000 00:00 0 
+Size:                132 kB
+Rss:                 132 kB
+Pss:                 132 kB
+Private_Dirty:       132 kB
+Anonymous:           132 kB
+Swap:                 12 kB
+7ffd4e1f0000-7ffd4e211000 rw-p 00000000 00:00 0                          [stack]
+Rss:                   4 kB
+Pss:                   4 kB
+";
+
 static PROC_STATM: &str = "2926 1266 827 22 0 489 0";
 
+static PROC_STATUS: &str = "Name:\thtop: test
+Umask:\t0022
+State:\tS (sleeping)
+Pid:\t17008
+Uid:\t1000\t0\t0\t0
+Gid:\t100\t100\t100\t100
+FDSize:\t64
+Groups:\t10 100 998 
+VmPeak:\t   11704 kB
+";
+
+static PROC_STATUS_CAPS: &str = "Name:\tnginx
+CapInh:\t0000000000000000
+CapPrm:\t0000000000002400
+CapEff:\t0000000000002400
+CapBnd:\t000001ffffffffff
+CapAmb:\t0000000000000000
+NoNewPrivs:\t1
+Seccomp:\t2
+Seccomp_filters:\t1
+";
+
+static PROC_LIMITS: &str = "Limit                     Soft Limit           Hard Limit           Units     
+Max cpu time              unlimited            unlimited            seconds   
+Max stack size            8388608              unlimited            bytes     
+Max open files            1024                 524288               files     
+Max nice priority         0                    0                    
+";
+
+static PASSWD: &str = "root:x:0:0::/root:/bin/bash
+# comment
+wojtas:x:1000:100::/home/wojtas:/bin/zsh
+toor:x:0:0::/root:/bin/sh
+";
+
+static GROUP: &str = "root:x:0:root
+wheel:x:10:wojtas
+users:x:100:
+docker:x:998:wojtas
+";
+
 static PROC_CMDLINE: &str = "htop\u{0}-t\u{0}--no-color\u{0}";
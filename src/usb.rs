@@ -0,0 +1,89 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct UsbDevice {
+    /// Name of the device in sysfs like `1-1.2`, or `usb1` for root hubs
+    pub name: String,
+    pub bus: u32,
+    pub devnum: u32,
+    /// Port path from the root hub like `1.2`, empty for root hubs
+    pub port: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial: Option<String>,
+    /// Negotiated speed in Mbit/s
+    pub speed: Option<String>,
+    /// Drivers bound to the device interfaces
+    pub drivers: Vec<String>,
+    /// Devices connected to this one if it is a hub
+    pub children: Vec<UsbDevice>,
+}
+impl UsbDevice {
+    /// Returns sysfs name of the hub this device is connected to
+    pub(crate) fn parent_name(&self) -> Option<String> {
+        if self.port.is_empty() {
+            None
+        } else {
+            match self.name.rfind('.') {
+                Some(i) => Some(self.name[..i].to_string()),
+                // bus is taken from the name as busnum may be unreadable
+                None => self.name.split_once('-').map(|(bus, _)| format!("usb{}", bus)),
+            }
+        }
+    }
+
+    fn port_numbers(&self) -> Vec<u32> {
+        self.port.split('.').filter_map(|p| p.parse::<u32>().ok()).collect()
+    }
+
+    /// Returns device and its descendants matching vendor and product id
+    pub fn find(&self, vendor_id: u16, product_id: u16) -> Option<&UsbDevice> {
+        if self.vendor_id == vendor_id && self.product_id == product_id {
+            Some(self)
+        } else {
+            self.children.iter().find_map(|child| child.find(vendor_id, product_id))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct UsbDevices {
+    /// Root hubs with all devices nested in `children`
+    pub devices: Vec<UsbDevice>,
+}
+impl UsbDevices {
+    /// Builds a tree out of flat list of devices
+    pub(crate) fn from_flat(mut flat: Vec<UsbDevice>) -> UsbDevices {
+        // attach the deepest devices first so their subtrees are complete when their hub gets attached
+        flat.sort_by_key(|dev| (!dev.port.is_empty(), dev.port.matches('.').count()));
+        let mut devices = vec![];
+        while let Some(dev) = flat.pop() {
+            if let Some(parent) = dev.parent_name().and_then(|parent| flat.iter_mut().find(|d| d.name == parent)) {
+                parent.children.push(dev);
+            } else {
+                devices.push(dev);
+            }
+        }
+        fn sort(devices: &mut [UsbDevice]) {
+            devices.sort_by_key(|dev| (dev.bus, dev.port_numbers()));
+            devices.iter_mut().for_each(|dev| sort(&mut dev.children));
+        }
+        sort(&mut devices);
+        UsbDevices { devices }
+    }
+
+    /// Returns first device anywhere in the tree matching vendor and product id
+    pub fn find(&self, vendor_id: u16, product_id: u16) -> Option<&UsbDevice> {
+        self.devices.iter().find_map(|dev| dev.find(vendor_id, product_id))
+    }
+}
+impl IntoIterator for UsbDevices {
+    type Item = UsbDevice;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.devices.into_iter()
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static CACHE: OnceLock<UserCache> = OnceLock::new();
+
+/// User and group names from `/etc/passwd` and `/etc/group`. Accounts provided
+/// only by other NSS sources like LDAP aren't resolved.
+#[derive(Debug, Default, PartialEq)]
+pub struct UserCache {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+impl UserCache {
+    /// Returns cache loaded on first use
+    pub fn get() -> &'static UserCache {
+        CACHE.get_or_init(|| {
+            let passwd = fs::read_to_string("/etc/passwd").unwrap_or_default();
+            let group = fs::read_to_string("/etc/group").unwrap_or_default();
+            UserCache::parse(&passwd, &group)
+        })
+    }
+
+    pub(crate) fn parse(passwd: &str, group: &str) -> UserCache {
+        // both files have name in the first field and id in the third
+        let ids = |out: &str| {
+            let mut ids = HashMap::new();
+            let entries = out.lines().filter(|l| !l.starts_with('#')).filter_map(|l| {
+                let fields: Vec<&str> = l.split(':').collect();
+                Some((fields.get(2)?.parse::<u32>().ok()?, fields[0].to_string()))
+            });
+            // first entry wins for shared ids like root and toor, same as getpwuid
+            for (id, name) in entries {
+                ids.entry(id).or_insert(name);
+            }
+            ids
+        };
+        UserCache { users: ids(passwd), groups: ids(group) }
+    }
+
+    /// Returns user name or uid as string if it's unknown
+    pub fn user(&self, uid: u32) -> String {
+        self.users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+    }
+
+    /// Returns group name or gid as string if it's unknown
+    pub fn group(&self, gid: u32) -> String {
+        self.groups.get(&gid).cloned().unwrap_or_else(|| gid.to_string())
+    }
+}
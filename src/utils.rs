@@ -59,6 +59,71 @@ pub fn conv_hex_to_ip(hex_addr: &str) -> Result<String> {
     }
 }
 
+/// Parses sizes like `512 KB` or `32K` into bytes
+pub fn parse_size(s: &str) -> u64 {
+    let s = s.trim();
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).collect::<String>();
+    let n = digits.parse::<u64>().unwrap_or(0);
+    match s[digits.len()..].trim().chars().next() {
+        Some('K') | Some('k') => n * 1024,
+        Some('M') => n * u64::pow(1024, 2),
+        Some('G') => n * u64::pow(1024, 3),
+        _ => n,
+    }
+}
+
+/// Parses user given sizes like `100m`, `1.5G` or `512 KiB` into bytes, units are powers of 1024.
+/// Unlike `parse_size` anything that isn't a valid size is an error.
+pub fn parse_byte_size(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let n = number.parse::<f64>().map_err(|_| anyhow!("invalid size {}", s))?;
+    let power = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return Err(anyhow!("invalid size unit in {}, use K, M, G or T", s)),
+    };
+    let bytes = n * 1024f64.powi(power);
+    if !bytes.is_finite() || bytes > u64::MAX as f64 {
+        return Err(anyhow!("size {} is too large", s));
+    }
+    Ok(bytes.round() as u64)
+}
+
+/// Parses user given seconds like `5` or `0.5`, rejecting negative and non-finite values
+pub fn parse_seconds(s: &str) -> Result<Duration> {
+    let secs = s.trim().parse::<f64>().map_err(|_| anyhow!("invalid number of seconds {}", s))?;
+    Duration::try_from_secs_f64(secs).map_err(|_| anyhow!("seconds must be a finite positive number, got {}", s))
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal number
+pub fn parse_num(s: &str) -> Option<u32> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse::<u32>().ok(),
+    }
+}
+
+/// Parses cpu lists like `0-3,8,10-11` into cpu ids
+pub fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = vec![];
+    for range in s.trim().split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => cpus.extend(range.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
 pub fn is_numeric(s: &str) -> bool {
     for c in s.chars() {
         if !c.is_numeric() {
@@ -68,6 +133,58 @@ pub fn is_numeric(s: &str) -> bool {
     true
 }
 
+/// Returns seconds since unix epoch
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Formats seconds since unix epoch as local date and time
+pub fn conv_date(secs: u64) -> String {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return "".to_string();
+    }
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Returns machine hardware name like `x86_64`
+pub fn uname_machine() -> String {
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } != 0 {
+        return "".to_string();
+    }
+    let machine = uts.machine.iter().take_while(|c| **c != 0).map(|c| *c as u8).collect::<Vec<u8>>();
+    String::from_utf8_lossy(&machine).to_string()
+}
+
+/// Returns hypervisor vendor signature from cpuid leaf 0x40000000 like `KVMKVMKVM`,
+/// or None when the cpu doesn't report running under a hypervisor
+#[cfg(target_arch = "x86_64")]
+pub fn cpuid_hypervisor() -> Option<String> {
+    use std::arch::x86_64::__cpuid;
+    #[allow(unused_unsafe)]
+    let (features, leaf) = unsafe { (__cpuid(1), __cpuid(0x4000_0000)) };
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+    let signature = [leaf.ebx, leaf.ecx, leaf.edx].iter().flat_map(|r| r.to_le_bytes()).collect::<Vec<u8>>();
+    Some(String::from_utf8_lossy(&signature).trim_end_matches('\0').to_string())
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn cpuid_hypervisor() -> Option<String> {
+    None
+}
+
 pub fn page_size() -> u64 {
     unsafe { libc::sysconf(libc::_SC_PAGE_SIZE) as u64 }
 }
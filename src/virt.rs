@@ -0,0 +1,89 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Hypervisor {
+    Kvm,
+    Qemu,
+    Xen,
+    Vmware,
+    HyperV,
+    VirtualBox,
+    /// CPU reports running under a hypervisor but it couldn't be identified
+    Unknown,
+}
+impl Hypervisor {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hypervisor::Kvm => "KVM",
+            Hypervisor::Qemu => "QEMU",
+            Hypervisor::Xen => "Xen",
+            Hypervisor::Vmware => "VMware",
+            Hypervisor::HyperV => "Hyper-V",
+            Hypervisor::VirtualBox => "VirtualBox",
+            Hypervisor::Unknown => "unknown hypervisor",
+        }
+    }
+
+    /// Identifies hypervisor from cpuid vendor signature
+    pub(crate) fn from_cpuid(signature: &str) -> Option<Hypervisor> {
+        match signature {
+            "KVMKVMKVM" => Some(Hypervisor::Kvm),
+            // tiny code generator, qemu emulating the cpu without kvm
+            "TCGTCGTCGTCG" => Some(Hypervisor::Qemu),
+            "XenVMMXenVMM" => Some(Hypervisor::Xen),
+            "VMwareVMware" => Some(Hypervisor::Vmware),
+            "Microsoft Hv" => Some(Hypervisor::HyperV),
+            "VBoxVBoxVBox" => Some(Hypervisor::VirtualBox),
+            _ => None,
+        }
+    }
+
+    /// Identifies hypervisor from DMI system vendor or product name. KVM guests usually
+    /// report QEMU here as it emulates the machine, so cpuid is checked first.
+    pub(crate) fn from_dmi(dmi: &Dmi) -> Option<Hypervisor> {
+        [&dmi.sys_vendor, &dmi.product_name, &dmi.bios_vendor].iter().filter_map(|s| s.as_deref()).find_map(|s| match s
+        {
+            s if s.contains("KVM") => Some(Hypervisor::Kvm),
+            s if s.contains("QEMU") => Some(Hypervisor::Qemu),
+            s if s.contains("Xen") => Some(Hypervisor::Xen),
+            s if s.contains("VMware") => Some(Hypervisor::Vmware),
+            s if s.contains("VirtualBox") || s.contains("innotek") => Some(Hypervisor::VirtualBox),
+            "Virtual Machine" => Some(Hypervisor::HyperV),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Container {
+    Docker,
+    Podman,
+    Lxc,
+    Kubernetes,
+    /// Value of `container` variable of PID 1 set by other runtimes like `systemd-nspawn`
+    Other(String),
+}
+impl Container {
+    pub fn name(&self) -> &str {
+        match self {
+            Container::Docker => "Docker",
+            Container::Podman => "Podman",
+            Container::Lxc => "LXC",
+            Container::Kubernetes => "Kubernetes",
+            Container::Other(name) => name,
+        }
+    }
+}
+
+/// Where rustop is running. Memory, CPU and load figures inside a container
+/// or virtual machine describe the guest, not the physical host.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Environment {
+    pub hypervisor: Option<Hypervisor>,
+    pub container: Option<Container>,
+}
+impl Environment {
+    pub fn is_bare_metal(&self) -> bool {
+        self.hypervisor.is_none() && self.container.is_none()
+    }
+}
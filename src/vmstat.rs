@@ -0,0 +1,111 @@
+use super::*;
+use std::time::Instant;
+
+/// Counters from `/proc/vmstat`, in pages or events since boot
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct VmStat {
+    pub pgfault: u64,
+    pub pgmajfault: u64,
+    /// Pages read from and written to block devices
+    pub pgpgin: u64,
+    pub pgpgout: u64,
+    /// Pages swapped in and out
+    pub pswpin: u64,
+    pub pswpout: u64,
+    pub oom_kill: u64,
+    pub compact_stall: u64,
+    pub compact_fail: u64,
+    pub compact_success: u64,
+}
+
+/// Kernel wide lines of `/proc/stat`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct KernelStat {
+    /// Context switches since boot
+    pub ctxt: u64,
+    /// Interrupts serviced since boot
+    pub intr: u64,
+    pub softirq: u64,
+    /// Processes and threads created since boot
+    pub processes: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+    /// Boot time in seconds since unix epoch
+    pub btime: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct KernelCounters {
+    pub vmstat: VmStat,
+    pub stat: KernelStat,
+}
+impl KernelCounters {
+    /// Returns per second rates of counters which grew since `prev` was read `elapsed` ago
+    pub fn rates(&self, prev: &KernelCounters, elapsed: Duration) -> KernelRates {
+        let secs = elapsed.as_secs_f64();
+        let rate = |now: u64, before: u64| if secs > 0. { now.saturating_sub(before) as f64 / secs } else { 0. };
+        let (vm, prev_vm) = (&self.vmstat, &prev.vmstat);
+        let page = utils::page_size() as f64;
+        KernelRates {
+            ctxsw: rate(self.stat.ctxt, prev.stat.ctxt),
+            interrupts: rate(self.stat.intr, prev.stat.intr),
+            softirqs: rate(self.stat.softirq, prev.stat.softirq),
+            forks: rate(self.stat.processes, prev.stat.processes),
+            page_faults: rate(vm.pgfault, prev_vm.pgfault),
+            major_faults: rate(vm.pgmajfault, prev_vm.pgmajfault),
+            swap_in: rate(vm.pswpin, prev_vm.pswpin) * page,
+            swap_out: rate(vm.pswpout, prev_vm.pswpout) * page,
+            // pgpgin and pgpgout are counted in kilobytes despite the name
+            block_in: rate(vm.pgpgin, prev_vm.pgpgin) * 1024.,
+            block_out: rate(vm.pgpgout, prev_vm.pgpgout) * 1024.,
+            oom_kills: vm.oom_kill.saturating_sub(prev_vm.oom_kill),
+            procs_running: self.stat.procs_running,
+            procs_blocked: self.stat.procs_blocked,
+        }
+    }
+}
+
+/// Kernel activity per second like `vmstat 1` shows it
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct KernelRates {
+    pub ctxsw: f64,
+    pub interrupts: f64,
+    pub softirqs: f64,
+    pub forks: f64,
+    pub page_faults: f64,
+    pub major_faults: f64,
+    /// Bytes per second
+    pub swap_in: f64,
+    pub swap_out: f64,
+    pub block_in: f64,
+    pub block_out: f64,
+    /// OOM kills during the interval
+    pub oom_kills: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+/// Computes rates between consecutive reads of kernel counters
+#[derive(Debug)]
+pub struct KernelSampler {
+    prev: KernelCounters,
+    at: Instant,
+}
+impl KernelSampler {
+    pub async fn new() -> Result<KernelSampler> {
+        Ok(KernelSampler { prev: procfs::kernel_counters().await?, at: Instant::now() })
+    }
+
+    /// Returns rates since the previous sample, waiting until at least `min_interval` passed
+    pub async fn sample(&mut self, min_interval: Duration) -> Result<KernelRates> {
+        let elapsed = self.at.elapsed();
+        if elapsed < min_interval {
+            async_std::task::sleep(min_interval - elapsed).await;
+        }
+        let now = procfs::kernel_counters().await?;
+        let rates = now.rates(&self.prev, self.at.elapsed());
+        self.prev = now;
+        self.at = Instant::now();
+        Ok(rates)
+    }
+}
@@ -0,0 +1,337 @@
+use super::ps::*;
+use super::*;
+use async_std::os::unix::net::UnixDatagram;
+use async_std::stream::Stream;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryInto;
+use std::future::Future;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ProcessEvent {
+    Started(Process),
+    /// Process as it was last seen before exiting
+    Exited(Process),
+    /// Process changed its name, command line, parent or effective user. Kernel threads
+    /// renaming themselves and processes only switching state are left out.
+    Changed {
+        old: Box<Process>,
+        new: Process,
+    },
+}
+impl ProcessEvent {
+    pub fn process(&self) -> &Process {
+        match self {
+            ProcessEvent::Started(p) | ProcessEvent::Exited(p) => p,
+            ProcessEvent::Changed { new, .. } => new,
+        }
+    }
+}
+
+/// Compares consecutive `ps::tree()` snapshots and reports differences between them.
+/// When the kernel proc connector can be used, which needs CAP_NET_ADMIN, forks, execs
+/// and exits are also reported as they happen and snapshots only catch up on the rest.
+/// Without it processes living shorter than the interval are not seen at all.
+/// Processes are identified by pid and start time so a reused pid shows up as an exit
+/// followed by a start. Whether a process matches the filter is decided when it is first
+/// seen or changes, so exits are reported even though an exited process can't be read anymore.
+#[derive(Debug)]
+pub struct ProcessWatcher {
+    interval: Duration,
+    filter: Option<ProcessFilter>,
+    /// Last seen processes and whether they matched the filter
+    known: HashMap<(u32, u64), (Process, bool)>,
+    pending: VecDeque<ProcessEvent>,
+    connector: Option<ProcConnector>,
+    snapshot_at: Instant,
+}
+impl ProcessWatcher {
+    /// Takes the first snapshot, processes already running don't produce `Started` events
+    pub fn new(interval: Duration) -> Result<ProcessWatcher> {
+        let mut watcher = ProcessWatcher::empty(interval);
+        // subscribing first so that nothing started in between is missed
+        watcher.connector = ProcConnector::new().ok();
+        watcher.diff(tree()?);
+        Ok(watcher)
+    }
+
+    pub(crate) fn empty(interval: Duration) -> ProcessWatcher {
+        ProcessWatcher {
+            interval,
+            filter: None,
+            known: HashMap::new(),
+            pending: VecDeque::new(),
+            connector: None,
+            snapshot_at: Instant::now(),
+        }
+    }
+
+    /// Returns true if events come from the kernel proc connector and not only from snapshots
+    pub fn is_event_driven(&self) -> bool {
+        self.connector.is_some()
+    }
+
+    /// Reports only events of processes matching `filter`
+    pub fn filter(mut self, filter: ProcessFilter) -> ProcessWatcher {
+        self.filter = Some(filter);
+        let uptime = Self::uptime();
+        let mut known = std::mem::take(&mut self.known);
+        for (p, matched) in known.values_mut() {
+            *matched = self.matches(p, uptime);
+        }
+        self.known = known;
+        self
+    }
+
+    fn uptime() -> f64 {
+        procfs::_uptime(&fs::read_to_string(SysProperty::Uptime.path()).unwrap_or_default())
+    }
+
+    fn matches(&self, p: &Process, uptime: f64) -> bool {
+        self.filter.as_ref().is_none_or(|f| f.matches(p, uptime))
+    }
+
+    /// Replaces known processes with `snapshot` and returns what changed in processes matching the filter
+    pub(crate) fn diff(&mut self, snapshot: Processes) -> Vec<ProcessEvent> {
+        let uptime = Self::uptime();
+        let mut events = vec![];
+        let seen = snapshot.iter().map(|p| (p.pid, p.starttime)).collect::<HashSet<_>>();
+        let mut exited = vec![];
+        self.known.retain(|key, (p, matched)| {
+            let running = seen.contains(key);
+            if !running && *matched {
+                exited.push(p.clone());
+            }
+            running
+        });
+        for new in snapshot {
+            self.update(new, uptime, &mut events);
+        }
+        exited.sort_by_key(|p| p.pid);
+        events.extend(exited.into_iter().map(ProcessEvent::Exited));
+        events
+    }
+
+    /// Updates known processes from proc connector events and returns what changed in processes matching the filter
+    pub(crate) fn apply(&mut self, connector_events: Vec<ConnectorEvent>) -> Vec<ProcessEvent> {
+        let uptime = Self::uptime();
+        let mut events = vec![];
+        for event in connector_events {
+            match event {
+                // a process exiting before it could be read is not reported at all
+                ConnectorEvent::Fork(pid) | ConnectorEvent::Exec(pid) => {
+                    if let Ok(new) = Process::new(pid) {
+                        self.update(new, uptime, &mut events);
+                    }
+                }
+                ConnectorEvent::Exit(pid) => {
+                    let key = self.known.keys().find(|(known, _)| *known == pid).copied();
+                    if let Some((p, true)) = key.and_then(|key| self.known.remove(&key)) {
+                        events.push(ProcessEvent::Exited(p));
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn update(&mut self, new: Process, uptime: f64, events: &mut Vec<ProcessEvent>) {
+        let key = (new.pid, new.starttime);
+        let matched = match self.known.remove(&key) {
+            Some((old, matched)) if Self::changed(&old, &new) => {
+                // a process leaving the filter still reports the change that made it leave
+                let now = self.matches(&new, uptime);
+                if matched || now {
+                    events.push(ProcessEvent::Changed { old: Box::new(old), new: new.clone() });
+                }
+                now
+            }
+            Some((_, matched)) => matched,
+            None => {
+                let matched = self.matches(&new, uptime);
+                if matched {
+                    events.push(ProcessEvent::Started(new.clone()));
+                }
+                matched
+            }
+        };
+        self.known.insert(key, (new, matched));
+    }
+
+    fn changed(old: &Process, new: &Process) -> bool {
+        // kworkers are renamed after the work they pick up
+        !new.is_kernel_thread()
+            && (old.name != new.name || old.cmd != new.cmd || old.ppid != new.ppid || old.euid != new.euid)
+    }
+
+    /// Waits for the next event, taking a snapshot every interval until something changes
+    pub async fn next(&mut self) -> Result<ProcessEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            match &self.connector {
+                Some(connector) => {
+                    let wait = self.interval.checked_sub(self.snapshot_at.elapsed()).unwrap_or_default();
+                    // a failed receive means events were lost on overflow, which the snapshot catches up on
+                    if let Ok(Ok(connector_events)) = async_std::future::timeout(wait, connector.recv()).await {
+                        let events = self.apply(connector_events);
+                        self.pending.extend(events);
+                        continue;
+                    }
+                }
+                None => async_std::task::sleep(self.interval).await,
+            }
+            let events = self.diff(tree()?);
+            self.snapshot_at = Instant::now();
+            self.pending.extend(events);
+        }
+    }
+
+    /// Turns the watcher into an endless stream of events
+    pub fn into_stream(self) -> ProcessEvents {
+        ProcessEvents { watcher: Some(self), next: None }
+    }
+}
+
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_CN_MCAST_IGNORE: u32 = 2;
+const PROC_EVENT_FORK: u32 = 0x1;
+const PROC_EVENT_EXEC: u32 = 0x2;
+const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+const NLMSG_HDRLEN: usize = 16;
+const CN_MSG_LEN: usize = 20;
+
+/// Event of the kernel proc connector, only whole processes are reported and not their threads
+#[derive(Debug, PartialEq)]
+pub(crate) enum ConnectorEvent {
+    Fork(u32),
+    Exec(u32),
+    Exit(u32),
+}
+
+/// Netlink subscription to process events of the kernel proc connector
+#[derive(Debug)]
+struct ProcConnector(UnixDatagram);
+impl ProcConnector {
+    fn new() -> Result<ProcConnector> {
+        let fd =
+            unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_CONNECTOR) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // async-std has no netlink sockets, but receiving from any datagram socket is the same recv(2)
+        let connector = ProcConnector(unsafe { UnixDatagram::from_raw_fd(fd) });
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = CN_IDX_PROC;
+        let len = std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+        if unsafe { libc::bind(fd, &addr as *const libc::sockaddr_nl as *const libc::sockaddr, len) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        connector.control(PROC_CN_MCAST_LISTEN)?;
+        Ok(connector)
+    }
+
+    /// Sends `op` to the proc connector, the kernel answers with an event ignored by the parser
+    fn control(&self, op: u32) -> Result<()> {
+        let len = NLMSG_HDRLEN + CN_MSG_LEN + 4;
+        let mut msg: Vec<u8> = Vec::with_capacity(len);
+        // nlmsghdr: length, type, flags, sequence number and port id
+        msg.extend(&(len as u32).to_ne_bytes());
+        msg.extend(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+        msg.extend(&0u16.to_ne_bytes());
+        msg.extend(&0u32.to_ne_bytes());
+        msg.extend(&std::process::id().to_ne_bytes());
+        // cn_msg: callback index and value, sequence, ack, payload length and flags
+        msg.extend(&CN_IDX_PROC.to_ne_bytes());
+        msg.extend(&CN_VAL_PROC.to_ne_bytes());
+        msg.extend(&0u32.to_ne_bytes());
+        msg.extend(&0u32.to_ne_bytes());
+        msg.extend(&4u16.to_ne_bytes());
+        msg.extend(&0u16.to_ne_bytes());
+        msg.extend(&op.to_ne_bytes());
+        if unsafe { libc::send(self.0.as_raw_fd(), msg.as_ptr() as *const libc::c_void, msg.len(), 0) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Vec<ConnectorEvent>> {
+        let mut buf = [0; 4096];
+        let n = self.0.recv(&mut buf).await?;
+        Ok(_connector_events(&buf[..n]))
+    }
+}
+impl Drop for ProcConnector {
+    fn drop(&mut self) {
+        let _ = self.control(PROC_CN_MCAST_IGNORE);
+    }
+}
+
+/// Parses netlink messages of the proc connector, events of threads and of other kinds are left out
+pub(crate) fn _connector_events(buf: &[u8]) -> Vec<ConnectorEvent> {
+    let u32_at = |msg: &[u8], at: usize| msg.get(at..at + 4).and_then(|b| b.try_into().ok()).map(u32::from_ne_bytes);
+    let mut events = vec![];
+    let mut rest = buf;
+    while let Some(len) = u32_at(rest, 0).map(|len| len as usize) {
+        if len < NLMSG_HDRLEN || len > rest.len() {
+            break;
+        }
+        let msg = &rest[..len];
+        // proc_event starts with what, cpu and timestamp followed by pid and tgid of the event
+        let what = NLMSG_HDRLEN + CN_MSG_LEN;
+        let data = what + 16;
+        let process = |at: usize| match (u32_at(msg, at), u32_at(msg, at + 4)) {
+            (Some(pid), Some(tgid)) if pid == tgid => Some(tgid),
+            _ => None,
+        };
+        let event = match u32_at(msg, what) {
+            // fork reports the parent before the child
+            Some(PROC_EVENT_FORK) => process(data + 8).map(ConnectorEvent::Fork),
+            Some(PROC_EVENT_EXEC) => process(data).map(ConnectorEvent::Exec),
+            Some(PROC_EVENT_EXIT) => process(data).map(ConnectorEvent::Exit),
+            _ => None,
+        };
+        events.extend(event);
+        rest = &rest[((len + 3) & !3).min(rest.len())..];
+    }
+    events
+}
+
+type NextEvent = Pin<Box<dyn Future<Output = (ProcessWatcher, Result<ProcessEvent>)> + Send>>;
+
+/// Stream of events returned by `ProcessWatcher::into_stream`
+pub struct ProcessEvents {
+    watcher: Option<ProcessWatcher>,
+    next: Option<NextEvent>,
+}
+impl Stream for ProcessEvents {
+    type Item = Result<ProcessEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.next.is_none() {
+            let mut watcher = match self.watcher.take() {
+                Some(watcher) => watcher,
+                None => return Poll::Ready(None),
+            };
+            self.next = Some(Box::pin(async move {
+                let event = watcher.next().await;
+                (watcher, event)
+            }));
+        }
+        match self.next.as_mut().map(|next| next.as_mut().poll(cx)) {
+            Some(Poll::Ready((watcher, event))) => {
+                self.next = None;
+                self.watcher = Some(watcher);
+                Poll::Ready(Some(event))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}